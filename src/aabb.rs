@@ -0,0 +1,137 @@
+use std::fmt;
+
+use crate::{hittable::Interval, point::Point, ray::Ray};
+
+/// An axis-aligned box, the bounds of an object for culling rays.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb {
+    min: Point,
+    max: Point,
+}
+
+impl Aabb {
+    /// The box with opposite corners `a` and `b`, in any order.
+    pub fn new(a: Point, b: Point) -> Self {
+        Self {
+            min: a.min(&b),
+            max: a.max(&b),
+        }
+    }
+
+    pub fn around_sphere(center: Point, radius: f64) -> Self {
+        let r = radius.abs();
+        let r = Point::new(r, r, r);
+        Self::new(center - r, center + r)
+    }
+
+    /// The smallest box holding every point in `points`, or `None` if
+    /// there are none.
+    pub fn around_points(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), |acc, p| {
+            Self::new(acc.min.min(&p), acc.max.max(&p))
+        }))
+    }
+
+    pub fn min(&self) -> Point {
+        self.min
+    }
+
+    pub fn max(&self) -> Point {
+        self.max
+    }
+
+    pub fn center(&self) -> Point {
+        0.5 * (self.min + self.max)
+    }
+
+    /// The length of the box along each axis.
+    pub fn size(&self) -> Point {
+        self.max - self.min
+    }
+
+    /// Widens every side thinner than `delta` to `delta`, so flat shapes like
+    /// quads still have a box rays can pass through.
+    pub fn padded(&self, delta: f64) -> Self {
+        let grow = |min: f64, max: f64| {
+            let pad = (0.5 * (delta - (max - min))).max(0.0);
+            (min - pad, max + pad)
+        };
+        let (x0, x1) = grow(self.min.x(), self.max.x());
+        let (y0, y1) = grow(self.min.y(), self.max.y());
+        let (z0, z1) = grow(self.min.z(), self.max.z());
+        Self::new(Point::new(x0, y0, z0), Point::new(x1, y1, z1))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(self.min.min(&other.min), self.max.max(&other.max))
+    }
+
+    pub fn corners(&self) -> [Point; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Point::new(a.x(), a.y(), a.z()),
+            Point::new(b.x(), a.y(), a.z()),
+            Point::new(a.x(), b.y(), a.z()),
+            Point::new(b.x(), b.y(), a.z()),
+            Point::new(a.x(), a.y(), b.z()),
+            Point::new(b.x(), a.y(), b.z()),
+            Point::new(a.x(), b.y(), b.z()),
+            Point::new(b.x(), b.y(), b.z()),
+        ]
+    }
+
+    /// Whether `r` passes through the box anywhere within `ray_t`, by
+    /// clipping the interval against each pair of axis planes in turn.
+    pub fn hit(&self, r: &Ray, ray_t: &Interval) -> bool {
+        let (mut t_min, mut t_max) = (ray_t.min(), ray_t.max());
+        for axis in 0..3 {
+            let inverse = 1.0 / r.direction().axis(axis);
+            let origin = r.origin().axis(axis);
+            let t0 = (self.min.axis(axis) - origin) * inverse;
+            let t1 = (self.max.axis(axis) - origin) * inverse;
+            let (t0, t1) = if inverse < 0.0 { (t1, t0) } else { (t0, t1) };
+            // Written so a NaN, from a ray lying in one of the planes, leaves
+            // the interval as it was.
+            if t0 > t_min {
+                t_min = t0;
+            }
+            if t1 < t_max {
+                t_max = t1;
+            }
+            if t_max <= t_min {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl fmt::Display for Aabb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "from {} to {}, center {}, size {}",
+            self.min,
+            self.max,
+            self.center(),
+            self.size()
+        )
+    }
+}
+
+#[test]
+fn rays_hit_boxes_only_within_the_interval() {
+    use crate::point::Vector;
+
+    let aabb = Aabb::new(Point::new(1.0, 1.0, 1.0), Point::new(-1.0, -1.0, -1.0));
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let towards = |y: f64| Ray::new(Point::new(0.0, y, 5.0), Vector::new(0.0, 0.0, -1.0));
+
+    assert!(aabb.hit(&towards(0.5), &interval));
+    assert!(!aabb.hit(&towards(1.5), &interval));
+    assert!(!aabb.hit(&towards(0.5), &Interval::new_set_interval(0.001, 3.0)));
+    let away = Ray::new(Point::new(0.0, 0.0, 5.0), Vector::new(0.0, 0.0, 1.0));
+    assert!(!aabb.hit(&away, &interval));
+}
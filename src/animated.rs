@@ -0,0 +1,90 @@
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind, ScatterRecord},
+    point::Point,
+    ray::Ray,
+};
+
+/// A material that changes over time, rebuilt by `at` for the time each ray
+/// carries, such as a light that pulses or a surface that shifts color
+/// across a sequence of frames.
+pub struct Animated<M> {
+    at: Box<dyn Fn(f64) -> M + Send + Sync>,
+}
+
+impl<M: Material> Animated<M> {
+    pub fn new(at: impl Fn(f64) -> M + Send + Sync + 'static) -> Self {
+        Self { at: Box::new(at) }
+    }
+}
+
+impl<M: Material> Material for Animated<M> {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::Animated
+    }
+
+    /// As the material is at time 0, since this isn't asked of a ray.
+    fn is_specular(&self) -> bool {
+        (self.at)(0.0).is_specular()
+    }
+
+    fn lights_scene(&self) -> bool {
+        (self.at)(0.0).lights_scene()
+    }
+
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
+        (self.at)(r_in.time()).scatter(r_in, rec)
+    }
+
+    fn scatter_record(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        (self.at)(r_in.time()).scatter_record(r_in, rec)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        (self.at)(r_in.time()).scattering_pdf(r_in, rec, scattered)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Point {
+        (self.at)(r_in.time()).emitted(r_in, rec)
+    }
+}
+
+#[test]
+fn light_brightens_across_frames() {
+    use std::sync::Arc;
+
+    use crate::{
+        camera::{test_init, Background, Camera, CameraInit},
+        diffuse_light::DiffuseLight,
+        hittable::HittableList,
+        sphere::Sphere,
+    };
+
+    let white = Point::new(1.0, 1.0, 1.0);
+    let light = Animated::new(move |time| DiffuseLight::new(white, 0.2 + 0.6 * time));
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        Arc::new(light),
+    )));
+
+    let brightness: Vec<_> = [0.0, 0.5, 1.0]
+        .into_iter()
+        .map(|time| {
+            let init = CameraInit {
+                samples_per_pixel: 1,
+                background: Background::Solid(Point::default()),
+                time,
+                ..test_init()
+            };
+            let image = Camera::new(1.0, 5, init).render_to_buffer(&world);
+            image.get_pixel(2, 2).0[0]
+        })
+        .collect();
+    let expected: Vec<_> = [0.2, 0.5, 0.8]
+        .iter()
+        .map(|x: &f64| (x.sqrt() * 255.0) as u8)
+        .collect();
+    assert_eq!(brightness, expected);
+}
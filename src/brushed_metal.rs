@@ -0,0 +1,65 @@
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind},
+    onb::Onb,
+    point::Point,
+    ray::Ray,
+};
+
+pub struct BrushedMetal {
+    color: Point,
+    fuzz_u: f64,
+    fuzz_v: f64,
+    tangent: Point,
+}
+
+impl BrushedMetal {
+    /// `fuzz_u` roughens the reflection along `tangent` (projected onto the
+    /// surface at the hit point) and `fuzz_v` across it.
+    pub fn new(color: Point, fuzz_u: f64, fuzz_v: f64, tangent: Point) -> Self {
+        Self {
+            color,
+            fuzz_u,
+            fuzz_v,
+            tangent,
+        }
+    }
+}
+
+impl Material for BrushedMetal {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::BrushedMetal
+    }
+
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
+        let reflected = r_in.unit_direction()?.reflect(&rec.normal);
+        let frame = Onb::from_w_and_tangent(&rec.normal, &self.tangent);
+        let offset = Point::random_in_unit_disk();
+        let fuzz = frame.local(Point::new(
+            self.fuzz_u * offset.x(),
+            self.fuzz_v * offset.y(),
+            0.0,
+        ));
+
+        Some((self.color, Ray::new(rec.p, reflected + fuzz)))
+    }
+}
+
+#[test]
+fn fuzz_stays_in_the_tangent_plane() {
+    use std::sync::Arc;
+
+    let metal = Arc::new(BrushedMetal::new(
+        Point::new(1.0, 1.0, 1.0),
+        0.5,
+        0.0,
+        Point::new(0.0, 1.0, 0.0),
+    ));
+    let mut rec = HitRecord::new(metal.clone());
+    rec.normal = Point::new(0.0, 0.0, 1.0);
+    let r_in = Ray::new(Point::new(0.0, 0.0, 1.0), Point::new(0.0, 0.0, -1.0));
+    for _ in 0..100 {
+        let direction = metal.scatter(&r_in, &rec).unwrap().1.direction();
+        assert_eq!((direction.x(), direction.z()), (0.0, 1.0));
+    }
+}
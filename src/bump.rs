@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind, PerturbsNormal},
+    point::Point,
+    texture::Texture,
+};
+
+/// Shades `inner` with normals tilted by the slope of `height`, a grayscale
+/// texture whose channels are averaged, scaled by `strength`.
+pub struct Bump {
+    inner: Arc<dyn Material>,
+    height: Arc<dyn Texture>,
+    strength: f64,
+}
+
+impl Bump {
+    const DELTA: f64 = 1e-3;
+
+    pub fn new(inner: Arc<dyn Material>, height: Arc<dyn Texture>, strength: f64) -> Self {
+        Self {
+            inner,
+            height,
+            strength,
+        }
+    }
+
+    fn height_at(&self, u: f64, v: f64, p: &Point) -> f64 {
+        let c = self.height.value(u, v, p);
+        (c.x() + c.y() + c.z()) / 3.0
+    }
+}
+
+impl PerturbsNormal for Bump {
+    const KIND: MaterialKind = MaterialKind::Bump;
+
+    fn inner(&self) -> &dyn Material {
+        self.inner.as_ref()
+    }
+
+    fn perturbed(&self, rec: &HitRecord) -> HitRecord {
+        let (u, v, d) = (rec.u, rec.v, Self::DELTA);
+        let du = (self.height_at(u + d, v, &rec.p) - self.height_at(u - d, v, &rec.p)) / (2.0 * d);
+        let dv = (self.height_at(u, v + d, &rec.p) - self.height_at(u, v - d, &rec.p)) / (2.0 * d);
+
+        let frame = rec.shading_frame();
+        let normal = rec.normal - self.strength * frame.local(Point::new(du, dv, 0.0));
+        rec.with_normal(normal.unit().unwrap_or(rec.normal))
+    }
+}
+
+#[test]
+fn flat_height_leaves_the_normal_unchanged() {
+    use crate::{lambertian::Lambertian, texture::SolidColor};
+
+    let inner = Arc::new(Lambertian::new(Point::default()));
+    let mut rec = HitRecord::new(inner.clone());
+    rec.normal = Point::new(0.0, 0.6, 0.8);
+    rec.u = 0.3;
+    rec.v = 0.7;
+
+    for level in [0.0, 0.5] {
+        let height = Arc::new(SolidColor::new(Point::new(level, level, level)));
+        let bump = Bump::new(inner.clone(), height, 2.0);
+        assert_eq!(bump.perturbed(&rec).normal, rec.normal);
+    }
+}
+
+#[test]
+fn samples_and_specularity_follow_the_inner_material() {
+    use crate::{
+        lambertian::Lambertian, material::Scattered, metal::Metal, point::Vector, ray::Ray,
+        texture::SolidColor,
+    };
+
+    let flat = Arc::new(SolidColor::new(Point::new(0.5, 0.5, 0.5)));
+    let lambertian = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)));
+    let bump = Bump::new(lambertian.clone(), flat.clone(), 1.0);
+    let mut rec = HitRecord::new(lambertian);
+    rec.normal = Vector::new(0.0, 1.0, 0.0);
+    let r_in = Ray::new(Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+
+    let record = bump.scatter_record(&r_in, &rec).unwrap();
+    assert!(matches!(record.scattered, Scattered::Pdf(_)));
+    let up = Ray::new(rec.p, rec.normal);
+    let density = bump.scattering_pdf(&r_in, &rec, &up);
+    assert!((density - std::f64::consts::FRAC_1_PI).abs() < 1e-12);
+
+    let mirror = Bump::new(
+        Arc::new(Metal::new(Point::new(1.0, 1.0, 1.0), 0.0)),
+        flat,
+        1.0,
+    );
+    assert!(mirror.is_specular());
+    assert!(!bump.is_specular());
+}
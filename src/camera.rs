@@ -1,11 +1,46 @@
 use crate::{
-    hittable::{HittableList, Interval},
-    point::{cross, Point, Vector},
+    cube_map::CubeMap,
+    denoise,
+    hittable::{HitRecord, Hittable, HittableList, Interval},
+    lambertian::Lambertian,
+    material::{MaterialKind, Scattered},
+    pdf::{CosinePdf, HittablePdf, MixturePdf, Pdf, SkyPdf, SkyTable},
+    point::{Point, Vector},
     ray::Ray,
-    utils::random_between,
+    stats::{self, RenderStats},
+    utils::{random_between, seed_random},
+};
+use image::{
+    codecs::{
+        jpeg::JpegEncoder,
+        png::{CompressionType, FilterType, PngEncoder},
+    },
+    EncodableLayout, GrayImage, ImageBuffer, ImageEncoder, ImageResult, Luma, Pixel,
+    PixelWithColorType, Rgb, RgbImage, Rgba, RgbaImage,
 };
-use image::ImageBuffer;
 use radians::Deg;
+use std::{
+    ffi::OsString,
+    fmt::Display,
+    fs::File,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// One pass of `Camera::render_pass`: each pixel's filter-weighted sum of
+/// linear colors and the total weight behind it, in row-major order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderPass {
+    pub sums: Vec<Point>,
+    pub weights: Vec<f64>,
+}
 
 pub struct Camera {
     image_width: u32,
@@ -14,14 +49,209 @@ pub struct Camera {
     pixel00_loc: Point,
     pixel_delta_u: Vector,
     pixel_delta_v: Vector,
-    samples_per_pixel: u32,
+    aa_samples: u32,
+    path_samples: u32,
     max_depth: u32,
     defocus_angle: f64,
     defocus_disk_u: Vector,
     defocus_disk_v: Vector,
+    jpeg_quality: Option<u8>,
+    png_compression: Option<CompressionType>,
+    output_depth: OutputDepth,
+    pixel_filter: PixelFilter,
+    background: Background,
+    quiet: bool,
+    threads: usize,
+    pixel_centers: bool,
+    near: f64,
+    far: f64,
+    ray_epsilon: f64,
+    max_specular_bounces: Option<u32>,
+    denoise: Option<f64>,
+    shading: Shading,
+    vignette: Option<f64>,
+    exposure: f64,
+    auto_exposure: bool,
+    alpha_background: bool,
+    time: f64,
+    shutter_open: f64,
+    shutter_close: f64,
+    cancel: Option<Arc<AtomicBool>>,
+    lights: Option<Arc<HittableList>>,
+    sky: Option<Arc<SkyTable>>,
+    output: Option<PathBuf>,
+    variance_output: Option<PathBuf>,
+    seed: u64,
+    settings: CameraInit,
+}
+
+/// What rays that escape the scene see.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Background {
+    /// Blends from `bottom` straight down to `top` straight up, by
+    /// `(0.5 * (y + 1)) ^ power` for a ray direction with height `y`.
+    Gradient {
+        top: Point,
+        bottom: Point,
+        power: f64,
+    },
+    Solid(Point),
+    CubeMap(Arc<CubeMap>),
+}
+
+/// The white-to-blue sky.
+impl Default for Background {
+    fn default() -> Self {
+        Self::Gradient {
+            top: Point::new(0.5, 0.7, 1.0),
+            bottom: Point::new(1.0, 1.0, 1.0),
+            power: 1.0,
+        }
+    }
+}
+
+impl Background {
+    pub fn color(&self, ray: &Ray) -> Point {
+        match self {
+            Self::Gradient { top, bottom, power } => {
+                let Some(unit_direction) = ray.unit_direction() else {
+                    return Point::default();
+                };
+                let a = (0.5 * (unit_direction.y() + 1.0)).powf(*power);
+                (1.0 - a) * *bottom + a * *top
+            }
+            Self::Solid(color) => *color,
+            Self::CubeMap(cube_map) => cube_map.color(&ray.direction()),
+        }
+    }
+
+    /// Directions drawn by the brightness of a gradient sky, `None` for
+    /// other backgrounds.
+    fn sky_table(&self) -> Option<SkyTable> {
+        let Self::Gradient { .. } = self else {
+            return None;
+        };
+        SkyTable::new(|y| {
+            let across = (1.0 - y * y).max(0.0).sqrt();
+            linear_luminance(self.color(&Ray::new(Point::default(), Vector::new(across, y, 0.0))))
+        })
+    }
 }
 
-#[derive(Default, Debug, Copy, Clone)]
+/// Bits per channel of the images `render` saves.
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub enum OutputDepth {
+    #[default]
+    Eight,
+    /// Smoother gradients, without the banding of 8 bits in skies and soft
+    /// light. Only PNG and the other formats that take 16 bits can hold it.
+    Sixteen,
+}
+
+/// Reconstruction filter used to weight the samples taken around each
+/// pixel center.
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub enum PixelFilter {
+    /// Uniform weight over the pixel's own square.
+    #[default]
+    Box,
+    /// Weight falling off linearly to zero one pixel away from the center.
+    Tent,
+    /// Gaussian weight with a standard deviation of half a pixel, truncated
+    /// at three standard deviations.
+    Gaussian,
+}
+
+impl PixelFilter {
+    const GAUSSIAN_SIGMA: f64 = 0.5;
+
+    /// Half-width, in pixels, of the area samples are taken from.
+    pub fn radius(&self) -> f64 {
+        match self {
+            Self::Box => 0.5,
+            Self::Tent => 1.0,
+            Self::Gaussian => 3.0 * Self::GAUSSIAN_SIGMA,
+        }
+    }
+
+    /// Filter weight for a sample offset `(dx, dy)` pixels from the pixel
+    /// center, normalized so the weights integrate to 1 over the support.
+    pub fn weight(&self, dx: f64, dy: f64) -> f64 {
+        let radius = self.radius();
+        if dx.abs() > radius || dy.abs() > radius {
+            return 0.0;
+        }
+        match self {
+            Self::Box => 1.0,
+            Self::Tent => (1.0 - dx.abs()) * (1.0 - dy.abs()),
+            Self::Gaussian => {
+                let variance = Self::GAUSSIAN_SIGMA * Self::GAUSSIAN_SIGMA;
+                (-(dx * dx + dy * dy) / (2.0 * variance)).exp()
+                    / (2.0 * std::f64::consts::PI * variance)
+            }
+        }
+    }
+}
+
+/// How `Camera::render` writes out the final pixels.
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub enum Shading {
+    #[default]
+    Color,
+    /// An 8-bit grayscale image of each pixel's luminance, as by `luminance`.
+    Luminance,
+    /// Instead of tracing paths, colors each pixel by how many bounding
+    /// boxes the ray through its center passes, from black for none through
+    /// blue to red at `HEATMAP_MAX` or more, to spot loose or overlapping
+    /// bounds.
+    BvhHeatmap,
+    /// Like `BvhHeatmap`, but counts the intersection tests, of bounding
+    /// boxes and objects alike, that finding the ray's closest hit took,
+    /// red at `COST_HEATMAP_MAX` or more.
+    CostHeatmap,
+    /// Instead of following materials, shades what each camera ray hits a
+    /// gray as light as the share of `samples` cosine-weighted rays from it
+    /// that escape without hitting anything within `radius`, for a matte
+    /// look that ignores lighting. What camera rays miss is white.
+    AmbientOcclusion { samples: u32, radius: f64 },
+    /// A focusing aid: the render desaturated to gray, with the pixels
+    /// whose first hit lies within `tolerance` of the `focus_dist` plane
+    /// tinted green.
+    FocusPeaking { tolerance: f64 },
+    /// Instead of tracing paths, colors each pixel by the id of the `IdTag`
+    /// around what the ray through its center hits, each id its own fixed
+    /// color, for masking objects out later. Untagged objects and misses
+    /// are black.
+    ObjectId,
+}
+
+/// Boxes pierced for a pixel to show as fully red under `Shading::BvhHeatmap`.
+pub const HEATMAP_MAX: u32 = 16;
+
+/// Intersection tests for a pixel to show as fully red under
+/// `Shading::CostHeatmap`.
+pub const COST_HEATMAP_MAX: u32 = 256;
+
+/// An image of 16-bit RGB pixels, as `render_rgb16` gives.
+pub type Rgb16Image = ImageBuffer<Rgb<u16>, Vec<u16>>;
+
+/// The log-average luminance `CameraInit::auto_exposure` brings images to.
+pub const AUTO_EXPOSURE_KEY: f64 = 0.18;
+
+/// The Rec. 709 luminance of a linear `color`.
+fn linear_luminance(color: Point) -> f64 {
+    0.2126 * color.x() + 0.7152 * color.y() + 0.0722 * color.z()
+}
+
+/// Converts every pixel of `image` to its Rec. 709 luminance.
+pub fn luminance(image: &RgbImage) -> GrayImage {
+    ImageBuffer::from_fn(image.width(), image.height(), |x, y| {
+        let [r, g, b] = image.get_pixel(x, y).0.map(f64::from);
+        Luma([(0.2126 * r + 0.7152 * g + 0.0722 * b).round() as u8])
+    })
+}
+
+#[derive(Default, Debug, Clone)]
 pub struct CameraInit {
     pub vfov: f64,
     pub lookfrom: Point,
@@ -30,12 +260,183 @@ pub struct CameraInit {
     pub focus_dist: f64,
     pub defocus_angle: f64,
     pub samples_per_pixel: u32,
+    /// Positions sampled within each pixel, overriding `samples_per_pixel`.
+    pub aa_samples: Option<u32>,
+    /// Paths traced from each of those positions, 1 if not set. A pixel
+    /// costs `aa_samples * path_samples` paths in total.
+    pub path_samples: Option<u32>,
+    /// JPEG quality from 1 to 100, `None` for the encoder's default.
+    pub jpeg_quality: Option<u8>,
+    /// PNG compression level, `None` for the encoder's default.
+    pub png_compression: Option<CompressionType>,
+    pub output_depth: OutputDepth,
+    pub pixel_filter: PixelFilter,
+    pub background: Background,
+    /// Without `with_lights`, samples bounces off diffuse surfaces toward
+    /// the bright parts of a `Background::Gradient` sky half of the time,
+    /// which cuts noise in scenes lit by a sky much brighter one way.
+    pub sky_sampling: bool,
+    /// Renders without printing a progress bar or statistics.
+    pub quiet: bool,
+    /// Worker threads to render rows on, 0 for one per available core.
+    pub threads: usize,
+    /// Shoots every sample through the exact pixel center instead of
+    /// jittering it over the filter's area, turning off antialiasing.
+    pub pixel_centers: bool,
+    /// Distances from the camera, along each camera ray, before and beyond
+    /// which geometry is clipped away. Only camera rays are clipped; bounces
+    /// still see the whole scene. `near` defaults to `ray_epsilon`.
+    pub near: Option<f64>,
+    pub far: Option<f64>,
+    /// How far a bounced ray must travel before it can hit anything, so it
+    /// doesn't hit the surface it leaves again through rounding error
+    /// (shadow acne). Rounding grows with the scene's coordinates, so large
+    /// scenes need more, while too much lets rays pass through thin or small
+    /// objects and corners. 0.001 if not set, which suits scenes measured in
+    /// units of about one.
+    pub ray_epsilon: Option<f64>,
+    /// Cuts off paths after this many specular bounces in a row, such as
+    /// light caught between facing mirrors or inside glass, which would
+    /// otherwise run to the maximum depth. The path ends as if it had run
+    /// out of depth, black beyond the last surface's own emission, but
+    /// without tracing the rest of it. Unlimited if not set.
+    pub max_specular_bounces: Option<u32>,
+    /// Strength of the bilateral denoise applied before gamma, as the
+    /// filter's spatial spread in pixels. Guided by each pixel's normal and
+    /// albedo, at the cost of one more camera ray per pixel. Off if not set.
+    pub denoise: Option<f64>,
+    pub shading: Shading,
+    /// Darkens the image toward its corners, by `1 - vignette * d^2` at a
+    /// distance `d` from the center where the corners are at 1. Off if not
+    /// set.
+    pub vignette: Option<f64>,
+    /// Stops to brighten the image by, or darken if negative, scaling the
+    /// linear colors by `2^exposure` before gamma.
+    pub exposure: f64,
+    /// Once rendered, scales the linear colors so the image's log-average
+    /// luminance lands on `AUTO_EXPOSURE_KEY`, a mid-gray, before
+    /// `exposure` is applied on top. Pixels left transparent by
+    /// `alpha_background` don't count, and heatmaps are left alone.
+    pub auto_exposure: bool,
+    /// Leaves the background out of the image: camera rays that see nothing
+    /// count as transparent, so each pixel's alpha is the share of its
+    /// samples that hit the scene and its color the average of those alone.
+    /// Light from the background still reaches the scene, and a
+    /// `ShadowCatcher` ground is left out too except for its shadows. Off by
+    /// default, for an opaque sky.
+    pub alpha_background: bool,
+    /// The moment the frame is taken at, carried by every ray, for scenes
+    /// that change over time; a sequence of frames steps it along.
+    pub time: f64,
+    /// When the shutter opens and closes, relative to `time`. Each ray is
+    /// given a time drawn evenly from between them, so whatever moves
+    /// meanwhile, like `Sphere::moving`, is blurred along its path; a
+    /// wider interval blurs more. Both default to 0, for a frame taken in
+    /// an instant.
+    pub shutter_open: f64,
+    pub shutter_close: f64,
+    /// Checked before each row is rendered: once another thread sets it, the
+    /// render stops early and returns what it has, with the rows it never
+    /// got to left black.
+    pub cancel: Option<Arc<AtomicBool>>,
+    /// Overrides the width given to `Camera::new` or `Camera::with_size`,
+    /// keeping to the aspect ratio asked for.
+    pub image_width: Option<u32>,
+    /// Bounces a path may take before it is cut off, 50 if not set.
+    pub max_depth: Option<u32>,
+    /// Where `render` saves the image, fractal.png if not set.
+    pub output: Option<PathBuf>,
+    /// Where `render` also saves a grayscale image of each pixel's noise:
+    /// the standard error of its samples' mean luminance, with gamma applied
+    /// like the colors. Bright spots are where more samples would help. Not
+    /// saved if not set.
+    pub variance_output: Option<PathBuf>,
+    /// Mixed into the seed of every pixel's random generator, so renders
+    /// with different seeds draw different samples.
+    pub seed: u64,
+}
+
+impl CameraInit {
+    /// The default settings with whatever of `with_env` is set.
+    pub fn from_env() -> io::Result<Self> {
+        Self::default().with_env()
+    }
+
+    /// Overrides settings from environment variables for batch renders:
+    /// `RT_WIDTH` for `image_width`, `RT_SAMPLES` for `samples_per_pixel`,
+    /// `RT_MAX_DEPTH` for `max_depth`, `RT_OUTPUT` for `output` and
+    /// `RT_SEED` for `seed`. Fails on a variable that doesn't parse.
+    pub fn with_env(self) -> io::Result<Self> {
+        self.with_vars(|name| std::env::var_os(name))
+    }
+
+    /// Like `with_env`, but reads the variables through `lookup`, which
+    /// gives `None` for those that aren't set.
+    pub fn with_vars(self, lookup: impl Fn(&str) -> Option<OsString>) -> io::Result<Self> {
+        let var = |name| lookup(name).map(|value| (name, value));
+        Ok(Self {
+            image_width: parse_var(var("RT_WIDTH"))?.or(self.image_width),
+            samples_per_pixel: parse_var(var("RT_SAMPLES"))?.unwrap_or(self.samples_per_pixel),
+            max_depth: parse_var(var("RT_MAX_DEPTH"))?.or(self.max_depth),
+            output: lookup("RT_OUTPUT").map(PathBuf::from).or(self.output),
+            seed: parse_var(var("RT_SEED"))?.unwrap_or(self.seed),
+            ..self
+        })
+    }
+
+    /// Sets `focus_dist` to the distance from `lookfrom` to `lookat`, so
+    /// whatever is at `lookat` is in focus.
+    pub fn auto_focus(self) -> Self {
+        Self {
+            focus_dist: (self.lookfrom - self.lookat).len(),
+            ..self
+        }
+    }
+}
+
+/// The value of a variable given as its name and text, `None` if it isn't
+/// set.
+fn parse_var<T>(var: Option<(&str, OsString)>) -> io::Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let Some((name, value)) = var else {
+        return Ok(None);
+    };
+    let invalid = |error: &dyn Display| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", name, error))
+    };
+    let value = value
+        .into_string()
+        .map_err(|value| invalid(&format!("{:?}: not unicode", value)))?;
+    value
+        .parse()
+        .map(Some)
+        .map_err(|error| invalid(&format!("{:?}: {}", value, error)))
 }
 
 impl Camera {
+    /// A camera `image_width` pixels wide and as tall as comes closest to
+    /// `aspect_ratio`, rounded down; use `with_size` to pick both exactly.
     pub fn new(aspect_ratio: f64, image_width: u32, init_params: CameraInit) -> Self {
+        let image_width = init_params.image_width.unwrap_or(image_width);
         let image_height = std::cmp::max((f64::from(image_width) / aspect_ratio) as u32, 1);
+        Self::with_size(image_width, image_height, init_params)
+    }
 
+    /// A camera rendering images of exactly `image_width` by `image_height`
+    /// pixels, each at least 1, with the viewport following their ratio.
+    pub fn with_size(image_width: u32, image_height: u32, init_params: CameraInit) -> Self {
+        let (image_width, image_height) = (image_width.max(1), image_height.max(1));
+        let (image_width, image_height) = match init_params.image_width {
+            Some(width) if width != image_width => {
+                let height = f64::from(image_height) * f64::from(width) / f64::from(image_width);
+                (width.max(1), (height.round() as u32).max(1))
+            }
+            _ => (image_width, image_height),
+        };
+        let settings = init_params.clone();
         let camera_center = init_params.lookfrom;
 
         let theta = Deg::new(init_params.vfov).rad();
@@ -46,14 +447,14 @@ impl Camera {
         let w = (init_params.lookfrom - init_params.lookat)
             .unit()
             .unwrap_or_default();
-        let u = cross(&init_params.vup, &w);
-        let v = cross(&w, &u);
+        let u = init_params.vup.cross(&w);
+        let v = w.cross(&u);
 
         let viewport_u = viewport_width * u;
         let viewport_v = -viewport_height * v;
 
-        let pixel_delta_u = (viewport_u / f64::from(image_width)).unwrap();
-        let pixel_delta_v = (viewport_v / f64::from(image_height)).unwrap();
+        let pixel_delta_u = (viewport_u / f64::from(image_width)).unwrap_or_default();
+        let pixel_delta_v = (viewport_v / f64::from(image_height)).unwrap_or_default();
 
         let viewport_upper_left = camera_center
             - (init_params.focus_dist * w)
@@ -62,6 +463,7 @@ impl Camera {
 
         let pixel00_loc = viewport_upper_left + 0.5 * (pixel_delta_u + pixel_delta_v);
 
+        let ray_epsilon = init_params.ray_epsilon.unwrap_or(0.001);
         let defocus_radius =
             init_params.focus_dist * (Deg::new(init_params.defocus_angle / 2.0).rad()).tan();
 
@@ -72,69 +474,964 @@ impl Camera {
             pixel00_loc,
             pixel_delta_u,
             pixel_delta_v,
-            samples_per_pixel: init_params.samples_per_pixel,
-            max_depth: 50,
+            aa_samples: init_params
+                .aa_samples
+                .unwrap_or(init_params.samples_per_pixel),
+            path_samples: init_params.path_samples.unwrap_or(1),
+            max_depth: init_params.max_depth.unwrap_or(50),
+            output: init_params.output.clone(),
+            variance_output: init_params.variance_output.clone(),
+            seed: init_params.seed,
             defocus_angle: init_params.defocus_angle,
             defocus_disk_u: u * defocus_radius,
             defocus_disk_v: v * defocus_radius,
+            jpeg_quality: init_params.jpeg_quality,
+            png_compression: init_params.png_compression,
+            output_depth: init_params.output_depth,
+            pixel_filter: init_params.pixel_filter,
+            sky: init_params
+                .sky_sampling
+                .then(|| init_params.background.sky_table())
+                .flatten()
+                .map(Arc::new),
+            background: init_params.background,
+            quiet: init_params.quiet,
+            threads: init_params.threads,
+            pixel_centers: init_params.pixel_centers,
+            near: init_params.near.unwrap_or(ray_epsilon),
+            far: init_params.far.unwrap_or(f64::MAX),
+            ray_epsilon,
+            max_specular_bounces: init_params.max_specular_bounces,
+            denoise: init_params.denoise,
+            shading: init_params.shading,
+            vignette: init_params.vignette,
+            exposure: init_params.exposure,
+            auto_exposure: init_params.auto_exposure,
+            alpha_background: init_params.alpha_background,
+            time: init_params.time,
+            shutter_open: init_params.shutter_open,
+            shutter_close: init_params.shutter_close,
+            cancel: init_params.cancel,
+            lights: None,
+            settings,
+        }
+    }
+
+    /// The `(width, height)` of the images this camera renders.
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.image_width, self.image_height)
+    }
+
+    /// The settings the camera was made with.
+    pub fn settings(&self) -> &CameraInit {
+        &self.settings
+    }
+
+    /// The lights given to `with_lights`, if any.
+    pub fn lights(&self) -> Option<&HittableList> {
+        self.lights.as_deref()
+    }
+
+    /// Samples bounces off diffuse surfaces toward `lights` half of the time,
+    /// which cuts noise a lot in scenes lit by small lights. `lights` should
+    /// hold copies of the emitters in the world; only their shapes matter.
+    pub fn with_lights(mut self, lights: HittableList) -> Self {
+        self.lights = Some(Arc::new(lights));
+        self
+    }
+
+    /// Renders and saves the image to `output`, fractal.png if not set,
+    /// panicking if it can't be saved.
+    pub fn render(&self, world: &HittableList) -> RenderStats {
+        let output = self.output.as_deref().unwrap_or(Path::new("fractal.png"));
+        self.render_to(world, output).unwrap()
+    }
+
+    /// Renders and saves the image to `path` whatever `output` says.
+    pub fn render_to(
+        &self,
+        world: &HittableList,
+        path: impl AsRef<Path>,
+    ) -> ImageResult<RenderStats> {
+        let output = path.as_ref();
+        let (colors, alpha, errors, stats) = self.render_linear(world, &mut self.progress_bar());
+        match self.output_depth {
+            OutputDepth::Sixteen => self.save_sixteen(&colors, &alpha, output),
+            OutputDepth::Eight if self.alpha_background => {
+                self.save(&self.rgba_image(&colors, &alpha), output)
+            }
+            OutputDepth::Eight => {
+                let imgbuf = self.rgb_image(&colors);
+                match self.shading {
+                    Shading::Color
+                    | Shading::BvhHeatmap
+                    | Shading::CostHeatmap
+                    | Shading::AmbientOcclusion { .. }
+                    | Shading::FocusPeaking { .. }
+                    | Shading::ObjectId => self.save(&imgbuf, output),
+                    Shading::Luminance => self.save(&luminance(&imgbuf), output),
+                }
+            }
+        }?;
+        if let Some(path) = &self.variance_output {
+            self.save(
+                &error_image(&errors, self.image_width, self.image_height),
+                path,
+            )?;
+        }
+        if !self.quiet {
+            println!("{}", stats);
         }
+        Ok(stats)
     }
 
-    pub fn render(&self, world: &HittableList) {
-        let mut imgbuf = ImageBuffer::new(self.image_width, self.image_height);
+    /// A quick version of this camera for trying out a composition: the same
+    /// view at a quarter of the size in each direction, with at most 16
+    /// samples per pixel, each bouncing at most 8 times.
+    pub fn preview_camera(&self) -> Self {
+        let image_width = (self.image_width / 4).max(1);
+        let image_height = (self.image_height / 4).max(1);
+        let pixel_delta_u =
+            self.pixel_delta_u * (f64::from(self.image_width) / f64::from(image_width));
+        let pixel_delta_v =
+            self.pixel_delta_v * (f64::from(self.image_height) / f64::from(image_height));
+        let upper_left = self.pixel00_loc - 0.5 * (self.pixel_delta_u + self.pixel_delta_v);
+        Self {
+            image_width,
+            image_height,
+            pixel00_loc: upper_left + 0.5 * (pixel_delta_u + pixel_delta_v),
+            pixel_delta_u,
+            pixel_delta_v,
+            aa_samples: self.aa_samples.min(16),
+            path_samples: 1,
+            max_depth: self.max_depth.min(8),
+            background: self.background.clone(),
+            cancel: self.cancel.clone(),
+            lights: self.lights.clone(),
+            sky: self.sky.clone(),
+            output: self.output.clone(),
+            variance_output: None,
+            settings: CameraInit {
+                aa_samples: Some(self.aa_samples.min(16)),
+                path_samples: Some(1),
+                max_depth: Some(self.max_depth.min(8)),
+                ..self.settings.clone()
+            },
+            ..*self
+        }
+    }
 
+    /// Renders with `preview_camera` and saves it beside `output`, with
+    /// .preview before the extension: fractal.preview.png if not set.
+    pub fn preview(&self, world: &HittableList) -> ImageResult<RenderStats> {
+        let camera = self.preview_camera();
+        let (imgbuf, stats) = camera.render_with_stats(world);
+        camera.save(&imgbuf, self.preview_path())?;
+        Ok(stats)
+    }
+
+    /// Where `preview` saves to.
+    fn preview_path(&self) -> PathBuf {
+        let output = self.output.as_deref().unwrap_or(Path::new("fractal.png"));
+        let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match output.extension() {
+            Some(extension) => format!("{}.preview.{}", stem, extension.to_string_lossy()),
+            None => format!("{}.preview", stem),
+        };
+        output.with_file_name(name)
+    }
+
+    pub fn render_to_buffer(&self, world: &HittableList) -> RgbImage {
+        self.render_with_stats(world).0
+    }
+
+    pub fn render_with_stats(&self, world: &HittableList) -> (RgbImage, RenderStats) {
+        self.render_with_progress(world, None)
+    }
+
+    /// Renders reporting the fraction of rows completed, from 0 to 1, to
+    /// `progress`, or to a progress bar on stdout if it is `None` and the
+    /// camera isn't quiet.
+    pub fn render_with_progress(
+        &self,
+        world: &HittableList,
+        progress: Option<Box<dyn FnMut(f32)>>,
+    ) -> (RgbImage, RenderStats) {
+        let mut progress = progress.unwrap_or_else(|| self.progress_bar());
+
+        let (colors, _, _, stats) = self.render_linear(world, &mut progress);
+        (self.rgb_image(&colors), stats)
+    }
+
+    /// The image of the linear `colors` of every pixel in row-major order.
+    fn rgb_image(&self, colors: &[Point]) -> RgbImage {
+        let width = self.image_width as usize;
+        ImageBuffer::from_fn(self.image_width, self.image_height, |x, y| {
+            self.display(colors[y as usize * width + x as usize], x, y)
+        })
+    }
+
+    /// A progress bar on stdout, or nothing if the camera is quiet.
+    fn progress_bar(&self) -> Box<dyn FnMut(f32)> {
+        if self.quiet {
+            return Box::new(|_| {});
+        }
         let mut bar = progress::BarBuilder::new()
             .left_cap("<")
             .right_cap(">")
             .empty_symbol("-")
             .filled_symbol("#")
             .build();
-
         bar.set_job_title("Rendering...");
+        let start = Instant::now();
+        Box::new(move |fraction| {
+            if let Some(left) = time_remaining(start.elapsed(), fraction) {
+                bar.set_job_title(&format!("Rendering... {}s left", left.as_secs()));
+            }
+            bar.reach_percent((fraction * 100.0) as i32)
+        })
+    }
+
+    /// Renders with 16 bits per channel, as `render_with_stats` does with 8.
+    pub fn render_rgb16(&self, world: &HittableList) -> (Rgb16Image, RenderStats) {
+        let (colors, _, _, stats) = self.render_linear(world, &mut |_| {});
+        let width = self.image_width as usize;
+        let imgbuf = ImageBuffer::from_fn(self.image_width, self.image_height, |x, y| {
+            Rgb::from(self.displayed(colors[y as usize * width + x as usize], x, y))
+        });
+        (imgbuf, stats)
+    }
+
+    /// Saves the linear `colors` and `alpha` at 16 bits per channel: with
+    /// alpha for `alpha_background`, gray for `Shading::Luminance` and RGB
+    /// otherwise.
+    fn save_sixteen(&self, colors: &[Point], alpha: &[f64], path: &Path) -> ImageResult<()> {
+        let width = self.image_width as usize;
+        let (w, h) = (self.image_width, self.image_height);
+        let pixel = |x: u32, y: u32| {
+            let i = y as usize * width + x as usize;
+            (
+                Rgb::<u16>::from(self.displayed(colors[i], x, y)).0,
+                alpha[i],
+            )
+        };
+        if self.alpha_background {
+            let alpha = |a: f64| (a.clamp(0.0, 1.0) * 65535.0).round() as u16;
+            self.save(
+                &ImageBuffer::from_fn(w, h, |x, y| {
+                    let ([r, g, b], a) = pixel(x, y);
+                    Rgba([r, g, b, alpha(a)])
+                }),
+                path,
+            )
+        } else if self.shading == Shading::Luminance {
+            self.save(
+                &ImageBuffer::from_fn(w, h, |x, y| {
+                    let [r, g, b] = pixel(x, y).0.map(f64::from);
+                    Luma([(0.2126 * r + 0.7152 * g + 0.0722 * b).round() as u16])
+                }),
+                path,
+            )
+        } else {
+            self.save(&ImageBuffer::from_fn(w, h, |x, y| Rgb(pixel(x, y).0)), path)
+        }
+    }
+
+    /// Renders with an alpha channel, transparent where `alpha_background`
+    /// leaves the background out, black and as opaque as the shadow on a
+    /// `ShadowCatcher`, and opaque everywhere otherwise.
+    pub fn render_rgba(&self, world: &HittableList) -> (RgbaImage, RenderStats) {
+        let (colors, alpha, _, stats) = self.render_linear(world, &mut |_| {});
+        (self.rgba_image(&colors, &alpha), stats)
+    }
+
+    /// The image of the linear `colors` and `alpha` of every pixel in
+    /// row-major order.
+    fn rgba_image(&self, colors: &[Point], alpha: &[f64]) -> RgbaImage {
+        let width = self.image_width as usize;
+        ImageBuffer::from_fn(self.image_width, self.image_height, |x, y| {
+            let i = y as usize * width + x as usize;
+            let Rgb([r, g, b]) = self.display(colors[i], x, y);
+            Rgba([r, g, b, alpha_byte(alpha[i])])
+        })
+    }
+
+    /// Renders straight into `buf`, `width * height` pixels of 3 bytes
+    /// (RGB) or 4 (RGBA, alpha as in `render_rgba`) each in row-major order, such as
+    /// a window's framebuffer. Fails without rendering if the dimensions
+    /// aren't the camera's or `buf` doesn't hold exactly that many pixels.
+    pub fn render_into(
+        &self,
+        world: &HittableList,
+        buf: &mut [u8],
+        width: u32,
+        height: u32,
+    ) -> io::Result<RenderStats> {
+        if (width, height) != self.dimensions() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "buffer is {}x{} but the camera renders {}x{}",
+                    width, height, self.image_width, self.image_height
+                ),
+            ));
+        }
+        let pixels = width as usize * height as usize;
+        let channels = match buf.len().checked_div(pixels) {
+            Some(channels @ (3 | 4)) if channels * pixels == buf.len() => channels,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "buffer of {} bytes is neither RGB nor RGBA for {} pixels",
+                        buf.len(),
+                        pixels
+                    ),
+                ))
+            }
+        };
+
+        let (colors, alpha, _, stats) = self.render_linear(world, &mut |_| {});
+        let pixels = colors.into_iter().zip(alpha);
+        for (i, (pixel, (color, alpha))) in buf.chunks_exact_mut(channels).zip(pixels).enumerate() {
+            let (x, y) = ((i % width as usize) as u32, (i / width as usize) as u32);
+            pixel[..3].copy_from_slice(&self.display(color, x, y).0);
+            if channels == 4 {
+                pixel[3] = alpha_byte(alpha);
+            }
+        }
+        Ok(stats)
+    }
+
+    /// The linear color, alpha and standard error of every pixel in
+    /// row-major order, the colors after any denoising, as `shading` asks
+    /// for.
+    fn render_linear(
+        &self,
+        world: &HittableList,
+        progress: &mut dyn FnMut(f32),
+    ) -> (Vec<Point>, Vec<f64>, Vec<f64>, RenderStats) {
+        let stats = RenderStats::default();
+        let width = self.image_width as usize;
+        let pixels = self.render_rows(
+            |x, y, record| {
+                let (color, alpha, error) = match self.shading {
+                    Shading::BvhHeatmap | Shading::CostHeatmap => {
+                        (self.heatmap(x, y, world), 1.0, 0.0)
+                    }
+                    Shading::ObjectId => (self.object_id(x, y, world), 1.0, 0.0),
+                    Shading::FocusPeaking { tolerance } => {
+                        let (color, alpha, error) = self.render_pixel(x, y, world, &stats, record);
+                        (self.focus_peak(color, x, y, world, tolerance), alpha, error)
+                    }
+                    _ => self.render_pixel(x, y, world, &stats, record),
+                };
+                let aov = self.denoise.map(|_| self.aov(x, y, world));
+                (color, alpha, error, aov)
+            },
+            progress,
+        );
+        let mut colors = Vec::with_capacity(pixels.len());
+        let mut alpha = Vec::with_capacity(pixels.len());
+        let mut errors = Vec::with_capacity(pixels.len());
+        let mut aovs = Vec::with_capacity(pixels.len());
+        for (c, a, e, aov) in pixels {
+            colors.push(c);
+            alpha.push(a);
+            errors.push(e);
+            aovs.push(aov);
+        }
+
+        if let Some(strength) = self.denoise {
+            let (normals, albedo): (Vec<_>, Vec<_>) =
+                aovs.into_iter().map(Option::unwrap_or_default).unzip();
+            colors = denoise::bilateral(&colors, width, strength, Some(&normals), Some(&albedo));
+        }
+        self.auto_expose(&mut colors, &alpha);
+        (colors, alpha, errors, stats)
+    }
+
+    /// Scales `colors` for `auto_exposure`, counting only those whose
+    /// `alpha` isn't 0.
+    fn auto_expose(&self, colors: &mut [Point], alpha: &[f64]) {
+        if !self.auto_exposure
+            || matches!(
+                self.shading,
+                Shading::BvhHeatmap | Shading::CostHeatmap | Shading::ObjectId
+            )
+        {
+            return;
+        }
+        // The small offset keeps black pixels from dragging the log to
+        // minus infinity.
+        let (log_sum, count) = colors
+            .iter()
+            .zip(alpha)
+            .filter(|(_, a)| **a > 0.0)
+            .fold((0.0, 0), |(sum, count), (c, _)| {
+                (sum + (1e-4 + linear_luminance(*c).max(0.0)).ln(), count + 1)
+            });
+        if count == 0 {
+            return;
+        }
+        let scale = AUTO_EXPOSURE_KEY / (log_sum / f64::from(count)).exp();
+        for color in colors {
+            *color *= scale;
+        }
+    }
+
+    /// Renders the linear colors, normals and albedo of every pixel in
+    /// row-major order, undenoised, for an external denoiser to work from;
+    /// see `denoise::export_guides`.
+    pub fn render_guides(&self, world: &HittableList) -> (Vec<Point>, Vec<Vector>, Vec<Point>) {
+        let stats = RenderStats::default();
+        let pixels = self.render_rows(
+            |x, y, record| {
+                let (color, _, _) = self.render_pixel(x, y, world, &stats, record);
+                let (normal, albedo) = self.aov(x, y, world);
+                (color, normal, albedo)
+            },
+            &mut |_| {},
+        );
+        let mut guides = (Vec::new(), Vec::new(), Vec::new());
+        for (color, normal, albedo) in pixels {
+            guides.0.push(color);
+            guides.1.push(normal);
+            guides.2.push(albedo);
+        }
+        guides
+    }
+
+    /// Renders every sample once more with the generator seeded from `seed`
+    /// as well as the pixel, keeping the unnormalized sums so passes from
+    /// different seeds, even different machines, can be merged with
+    /// `combine_passes`. Seed 0 draws the same samples as `render`. Passes
+    /// are never denoised.
+    pub fn render_pass(&self, world: &HittableList, seed: u64) -> RenderPass {
+        let stats = RenderStats::default();
+        let pixels = self.render_rows(
+            |x, y, record| {
+                let (sum, covered, _, _) = self.sample_pixel(x, y, seed, world, &stats, record);
+                (sum, covered)
+            },
+            &mut |_| {},
+        );
+        let (sums, weights) = pixels.into_iter().unzip();
+        RenderPass { sums, weights }
+    }
+
+    /// Averages `passes` rendered by this camera into the image one render
+    /// with all their samples would give.
+    pub fn combine_passes(&self, passes: &[RenderPass]) -> RgbImage {
+        let width = self.image_width as usize;
+        let pixels = width * self.image_height as usize;
+        let mut colors: Vec<Point> = (0..pixels)
+            .map(|i| {
+                let (sum, weight) = passes.iter().fold((Point::default(), 0.0), |acc, pass| {
+                    (acc.0 + pass.sums[i], acc.1 + pass.weights[i])
+                });
+                (sum / weight).unwrap_or_default()
+            })
+            .collect();
+        self.auto_expose(&mut colors, &vec![1.0; pixels]);
+        ImageBuffer::from_fn(self.image_width, self.image_height, |x, y| {
+            self.display(colors[y as usize * width + x as usize], x, y)
+        })
+    }
+
+    /// Exposes and gamma-corrects the linear `color` of pixel `(x, y)` for
+    /// display and applies the vignette.
+    fn display(&self, color: Point, x: u32, y: u32) -> Rgb<u8> {
+        Rgb::from(self.displayed(color, x, y))
+    }
+
+    /// What `display` shows for `color`, from 0 to 1 before clamping.
+    fn displayed(&self, color: Point, x: u32, y: u32) -> Point {
+        let color = color * self.exposure.exp2();
+        let Some(strength) = self.vignette else {
+            return color.sqrt();
+        };
+        let half_width = f64::from(self.image_width) / 2.0;
+        let half_height = f64::from(self.image_height) / 2.0;
+        let dx = (f64::from(x) + 0.5 - half_width) / half_width;
+        let dy = (f64::from(y) + 0.5 - half_height) / half_height;
+        // Squared distance from the center, 0 there and 1 in the corners.
+        let distance = (dx * dx + dy * dy) / 2.0;
+        color.sqrt() * (1.0 - strength * distance).max(0.0)
+    }
+
+    /// Computes `pixel` for every pixel on the configured number of threads,
+    /// reporting the fraction of rows done to `progress` as they finish.
+    /// Each thread passes one record of its own to every `pixel` it computes,
+    /// for `hit_into` to reuse. Returns the pixels in row-major order, with
+    /// the default for any left unrendered by `cancel`.
+    fn render_rows<T: Send + Default>(
+        &self,
+        pixel: impl Fn(u32, u32, &mut HitRecord) -> T + Sync,
+        progress: &mut dyn FnMut(f32),
+    ) -> Vec<T> {
+        let width = self.image_width as usize;
+        let mut pixels: Vec<Option<T>> = (0..width * self.image_height as usize)
+            .map(|_| None)
+            .collect();
+
+        let threads = match self.threads {
+            0 => thread::available_parallelism().map_or(1, |x| x.get()),
+            n => n,
+        };
+        let next_row = AtomicU32::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                let sender = sender.clone();
+                let (next_row, pixel) = (&next_row, &pixel);
+                scope.spawn(move || {
+                    let mut record = scratch_record();
+                    loop {
+                        if self.cancelled() {
+                            break;
+                        }
+                        let y = next_row.fetch_add(1, Ordering::Relaxed);
+                        if y >= self.image_height {
+                            break;
+                        }
+                        let row: Vec<_> = (0..self.image_width)
+                            .map(|x| pixel(x, y, &mut record))
+                            .collect();
+                        if sender.send((y, row)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(sender);
+
+            for (done, (y, row)) in receiver.iter().enumerate() {
+                let start = y as usize * width;
+                for (slot, value) in pixels[start..].iter_mut().zip(row) {
+                    *slot = Some(value);
+                }
+                progress((done + 1) as f32 / self.image_height as f32);
+            }
+        });
+
+        pixels.into_iter().map(Option::unwrap_or_default).collect()
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+
+    /// The linear color of pixel `(x, y)`, sampled as `render` samples it but
+    /// without denoising, auto exposure or anything written anywhere, to
+    /// time or test the tracer alone. Heatmap shading isn't traced and so
+    /// isn't followed here.
+    pub fn trace_pixel(&self, world: &HittableList, x: u32, y: u32) -> Point {
+        let mut record = scratch_record();
+        self.render_pixel(x, y, world, &RenderStats::default(), &mut record)
+            .0
+    }
+
+    /// Samples pixel `(x, y)` with the generator seeded from its position and
+    /// `seed`, so it comes out the same whichever thread renders it. Returns its color,
+    /// alpha and standard error.
+    fn render_pixel(
+        &self,
+        x: u32,
+        y: u32,
+        world: &HittableList,
+        stats: &RenderStats,
+        record: &mut HitRecord,
+    ) -> (Point, f64, f64) {
+        let (sum, covered, total, error) = self.sample_pixel(x, y, self.seed, world, stats, record);
+        let color = (sum / covered).unwrap_or_default();
+        (
+            color,
+            if total > 0.0 { covered / total } else { 1.0 },
+            error,
+        )
+    }
+
+    /// The filter-weighted sum of pixel `(x, y)`'s samples, the weight of
+    /// those that count toward its color, the total weight and the standard
+    /// error of their mean luminance, drawn from a generator seeded from the
+    /// pixel and `seed`. All samples count unless
+    /// `alpha_background` leaves out those that see only the background,
+    /// or counts those that see a shadow catcher as much as it is shadowed.
+    fn sample_pixel(
+        &self,
+        x: u32,
+        y: u32,
+        seed: u64,
+        world: &HittableList,
+        stats: &RenderStats,
+        record: &mut HitRecord,
+    ) -> (Point, f64, f64, f64) {
+        let pixel = u64::from(y) << 32 | u64::from(x);
+        seed_random(pixel ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        let ray_t = Interval::new_set_interval(self.near, self.far);
+        let (sum, covered, total, lum, lum_squares) = (0..self.aa_samples)
+            .map(|_| {
+                let (dx, dy) = self.pixel_sample_square();
+                let ray = self.get_ray(f64::from(x) + dx, f64::from(y) + dy);
+                let weight = self.pixel_filter.weight(dx, dy);
+                let mut primary_hit = false;
+                if self.alpha_background {
+                    if !world.hit_into(&ray, &ray_t, record) {
+                        return (Point::default(), 0.0, weight, 0.0);
+                    }
+                    if record.mat.kind() == MaterialKind::ShadowCatcher {
+                        stats.record_primary();
+                        let shadow = self.shadow_at(record, &ray, world);
+                        return (Point::default(), shadow * weight, weight, 0.0);
+                    }
+                    primary_hit = true;
+                }
+                let color = (0..self.path_samples).fold(Point::default(), |acc, _| {
+                    stats.record_primary();
+                    acc + match self.shading {
+                        Shading::AmbientOcclusion { samples, radius } => {
+                            self.ambient_occlusion(&ray, world, samples, radius)
+                        }
+                        // The first path starts from the hit found above.
+                        _ if std::mem::take(&mut primary_hit) => {
+                            self.hit_color(&ray, self.max_depth, 0, world, stats, record)
+                        }
+                        _ => self.ray_color(&ray, self.max_depth, 0, world, stats, record),
+                    }
+                });
+                let color = (color / f64::from(self.path_samples)).unwrap_or_default();
+                (color * weight, weight, weight, linear_luminance(color))
+            })
+            .fold(
+                (Point::new(0.0, 0.0, 0.0), 0.0, 0.0, 0.0, 0.0),
+                |acc, sample| {
+                    (
+                        acc.0 + sample.0,
+                        acc.1 + sample.1,
+                        acc.2 + sample.2,
+                        acc.3 + sample.3,
+                        acc.4 + sample.3 * sample.3,
+                    )
+                },
+            );
+        (
+            sum,
+            covered,
+            total,
+            standard_error(lum, lum_squares, self.aa_samples),
+        )
+    }
+
+    /// How shadowed the point `record` a camera `ray` hit is, from 0 for
+    /// fully lit to 1: the share of `SHADOW_PROBES` rays toward the lights,
+    /// or cosine-weighted toward the sky without any, that are blocked.
+    fn shadow_at(&self, record: &HitRecord, ray: &Ray, world: &HittableList) -> f64 {
+        const SHADOW_PROBES: u32 = 16;
+        let cosine = CosinePdf::new(&record.normal);
+        let probe = |direction| Ray::new(record.p, direction).with_time(ray.time());
+        let blocked = (0..SHADOW_PROBES)
+            .filter(|_| match &self.lights {
+                Some(lights) => {
+                    let probe = probe(lights.random(&record.p));
+                    let reach = Interval::new_set_interval(self.ray_epsilon, self.far);
+                    // Short of the light itself, which is also in the world.
+                    lights.hit_distance(&probe, &reach).is_some_and(|t| {
+                        let before = Interval::new_set_interval(self.ray_epsilon, t * 0.999);
+                        world.hit_any(&probe, &before)
+                    })
+                }
+                None => world.hit_any(&probe(cosine.generate()), &self.bounce_interval()),
+            })
+            .count();
+        blocked as f64 / f64::from(SHADOW_PROBES)
+    }
+
+    /// The `Shading::AmbientOcclusion` gray seen along camera ray `ray`.
+    fn ambient_occlusion(
+        &self,
+        ray: &Ray,
+        world: &HittableList,
+        samples: u32,
+        radius: f64,
+    ) -> Point {
+        let ray_t = Interval::new_set_interval(self.near, self.far);
+        let Some(record) = world.hit(ray, &ray_t) else {
+            return Point::new(1.0, 1.0, 1.0);
+        };
+        let reach = Interval::new_set_interval(self.ray_epsilon, radius);
+        let pdf = CosinePdf::new(&record.normal);
+        let open = (0..samples)
+            .filter(|_| {
+                let probe = Ray::new(record.p, pdf.generate()).with_time(ray.time());
+                !world.hit_any(&probe, &reach)
+            })
+            .count();
+        let open = if samples == 0 {
+            1.0
+        } else {
+            open as f64 / f64::from(samples)
+        };
+        Point::new(open, open, open)
+    }
+
+    /// The `Shading::BvhHeatmap` or `Shading::CostHeatmap` color of pixel
+    /// `(x, y)`.
+    fn heatmap(&self, x: u32, y: u32, world: &HittableList) -> Point {
+        let ray = self.get_ray(f64::from(x), f64::from(y));
+        let ray_t = Interval::new_set_interval(self.near, self.far);
+        let (count, max) = if self.shading == Shading::CostHeatmap {
+            let before = stats::intersection_tests();
+            world.hit(&ray, &ray_t);
+            let tests = stats::intersection_tests() - before;
+            (u32::try_from(tests).unwrap_or(u32::MAX), COST_HEATMAP_MAX)
+        } else {
+            (world.boxes_pierced(&ray, &ray_t), HEATMAP_MAX)
+        };
+        match count {
+            0 => Point::default(),
+            n => {
+                let t = f64::from(n.min(max)) / f64::from(max);
+                Point::new(t, 0.0, 1.0 - t)
+            }
+        }
+    }
+
+    /// The `Shading::FocusPeaking` color of pixel `(x, y)` rendered as
+    /// `color`: green-tinted if what the pinhole ray through its center hits
+    /// is within `tolerance` of the focus plane, gray otherwise.
+    fn focus_peak(
+        &self,
+        color: Point,
+        x: u32,
+        y: u32,
+        world: &HittableList,
+        tolerance: f64,
+    ) -> Point {
+        let gray = linear_luminance(color);
+        let gray = Point::new(gray, gray, gray);
+        let target = self.pixel00_loc
+            + (f64::from(x) * self.pixel_delta_u)
+            + (f64::from(y) * self.pixel_delta_v);
+        let ray = Ray::new_normalized(self.center, target - self.center);
+        let ray_t = Interval::new_set_interval(self.near, self.far);
+        let forward = (self.settings.lookat - self.settings.lookfrom)
+            .unit()
+            .unwrap_or_default();
+        match world.hit(&ray, &ray_t) {
+            // Depth along the view axis, since the focus plane is flat.
+            Some(record)
+                if ((record.p - self.center).dot(&forward) - self.settings.focus_dist).abs()
+                    <= tolerance =>
+            {
+                0.5 * gray + Point::new(0.0, 0.5, 0.0)
+            }
+            _ => gray,
+        }
+    }
+
+    /// The `Shading::ObjectId` color of pixel `(x, y)`.
+    fn object_id(&self, x: u32, y: u32, world: &HittableList) -> Point {
+        let ray = self.get_ray(f64::from(x), f64::from(y));
+        let ray_t = Interval::new_set_interval(self.near, self.far);
+        match world.hit(&ray, &ray_t).and_then(|record| record.id) {
+            Some(id) => id_color(id),
+            None => Point::default(),
+        }
+    }
+
+    /// Normal and albedo of whatever is seen through the center of pixel
+    /// `(x, y)`, for the denoiser. Misses have no normal and the background
+    /// color as albedo; lights their emission.
+    fn aov(&self, x: u32, y: u32, world: &HittableList) -> (Vector, Point) {
+        let ray = self.get_ray(f64::from(x), f64::from(y));
+        let ray_t = Interval::new_set_interval(self.near, self.far);
+        let Some(record) = world.hit(&ray, &ray_t) else {
+            return (Vector::default(), self.background.color(&ray));
+        };
+        let albedo = match record.mat.scatter(&ray, &record) {
+            Some((attenuation, _)) => attenuation,
+            None => record.mat.emitted(&ray, &record),
+        };
+        (record.normal, albedo)
+    }
+
+    /// Saves `image` in the format given by the extension of `path`, applying
+    /// the configured JPEG quality or PNG compression.
+    pub fn save<P>(
+        &self,
+        image: &ImageBuffer<P, Vec<P::Subpixel>>,
+        path: impl AsRef<Path>,
+    ) -> ImageResult<()>
+    where
+        P: Pixel + PixelWithColorType,
+        [P::Subpixel]: EncodableLayout,
+    {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("jpg" | "jpeg") => {
+                let writer = BufWriter::new(File::create(path)?);
+                let mut encoder = match self.jpeg_quality {
+                    Some(quality) => JpegEncoder::new_with_quality(writer, quality.clamp(1, 100)),
+                    None => JpegEncoder::new(writer),
+                };
+                encoder.encode_image(image)
+            }
+            Some("png") => {
+                let writer = BufWriter::new(File::create(path)?);
+                let encoder = match self.png_compression {
+                    Some(compression) => {
+                        PngEncoder::new_with_quality(writer, compression, FilterType::Adaptive)
+                    }
+                    None => PngEncoder::new(writer),
+                };
+                encoder.write_image(
+                    image.as_raw().as_bytes(),
+                    image.width(),
+                    image.height(),
+                    P::COLOR_TYPE,
+                )
+            }
+            _ => image.save(path),
+        }
+    }
 
-        for (x, y, pixel) in imgbuf.enumerate_pixels_mut() {
-            let sum = (0..self.samples_per_pixel)
-                .map(|_| {
-                    let ray = self.get_ray(f64::from(x), f64::from(y));
-                    Camera::ray_color(&ray, self.max_depth, world)
-                })
-                .fold(Point::new(0.0, 0.0, 0.0), |acc, point| acc + point);
-            let sum = (sum / f64::from(self.samples_per_pixel)).unwrap_or_default();
-            let sum = sum.sqrt();
+    /// Where along a bounced ray hits count.
+    fn bounce_interval(&self) -> Interval {
+        Interval::new_set_interval(self.ray_epsilon, f64::MAX)
+    }
 
-            *pixel = image::Rgb::from(sum);
-            bar.reach_percent((y as f64 / self.image_height as f64 * 100.0) as i32);
+    /// Color seen along `ray`, which follows `specular_chain` specular
+    /// bounces in a row. Every bounce overwrites `record` rather than
+    /// building a record of its own.
+    fn ray_color(
+        &self,
+        ray: &Ray,
+        depth: u32,
+        specular_chain: u32,
+        world: &HittableList,
+        stats: &RenderStats,
+        record: &mut HitRecord,
+    ) -> Point {
+        if depth == 0 {
+            return Point::default();
+        }
+        let ray_t = if depth == self.max_depth {
+            Interval::new_set_interval(self.near, self.far)
+        } else {
+            self.bounce_interval()
+        };
+        if world.hit_into(ray, &ray_t, record) {
+            self.hit_color(ray, depth, specular_chain, world, stats, record)
+        } else {
+            stats.record_miss();
+            self.background.color(ray)
         }
-        imgbuf.save("fractal.png").unwrap();
     }
 
-    fn ray_color(ray: &Ray, depth: u32, world: &HittableList) -> Point {
+    /// Color seen along `ray` given that it hit `record`, which the
+    /// bounces after overwrite.
+    fn hit_color(
+        &self,
+        ray: &Ray,
+        depth: u32,
+        specular_chain: u32,
+        world: &HittableList,
+        stats: &RenderStats,
+        record: &mut HitRecord,
+    ) -> Point {
         if depth == 0 {
             return Point::default();
         }
-        if let Some(record) = world.hit(ray, &Interval::new_set_interval(0.001, f64::MAX)) {
-            match record.mat.borrow().scatter(ray, &record) {
-                Some((attenuation, scattered)) => {
-                    Camera::ray_color(&scattered, depth - 1, world) * attenuation
+        let mat = &record.mat;
+        // Only camera rays and those that have just bounced off a mirror
+        // or glass see emitters that light nothing.
+        let emitted = if mat.lights_scene() || depth == self.max_depth || specular_chain > 0 {
+            mat.emitted(ray, record)
+        } else {
+            Point::default()
+        };
+        let specular_chain = if mat.is_specular() {
+            specular_chain + 1
+        } else {
+            0
+        };
+        if self
+            .max_specular_bounces
+            .is_some_and(|max| specular_chain > max)
+        {
+            return emitted;
+        }
+        let Some(scatter) = mat.scatter_record(ray, record) else {
+            return emitted;
+        };
+        let (scattered, weight) = match scatter.scattered {
+            Scattered::Ray(scattered) => (scattered.with_time(ray.time()), scatter.attenuation),
+            Scattered::Pdf(pdf) => {
+                let (direction, density) = match &self.lights {
+                    Some(lights) => {
+                        let toward_lights = HittablePdf::new(lights.as_ref(), record.p);
+                        let mixture = MixturePdf::new(&toward_lights, pdf.as_ref());
+                        let direction = mixture.generate();
+                        (direction, mixture.value(&direction))
+                    }
+                    None => match &self.sky {
+                        Some(sky) => {
+                            let toward_sky = SkyPdf::new(sky, &record.normal);
+                            let mixture = MixturePdf::new(&toward_sky, pdf.as_ref());
+                            let direction = mixture.generate();
+                            (direction, mixture.value(&direction))
+                        }
+                        None => {
+                            let direction = pdf.generate();
+                            (direction, pdf.value(&direction))
+                        }
+                    },
+                };
+                if density <= 0.0 || direction.near_zero() {
+                    return emitted;
                 }
-                None => Point::new(0.0, 0.0, 0.0),
+                let scattered = Ray::new(record.p, direction).with_time(ray.time());
+                let scattering_pdf = mat.scattering_pdf(ray, record, &scattered);
+                (scattered, scatter.attenuation * (scattering_pdf / density))
             }
+        };
+
+        // The last bounce's scattered ray is cut off, not traced.
+        if depth > 1 {
+            stats.record_scattered();
+        }
+        let incoming = self.ray_color(&scattered, depth - 1, specular_chain, world, stats, record);
+        let color = emitted + incoming * weight;
+        // A NaN or infinity would poison the whole pixel's average, so
+        // such samples are dropped as black.
+        if color.is_finite() {
+            color
         } else {
-            let unit_direction = ray.direction().unit().unwrap();
-            let a = 0.5 * (unit_direction.y() + 1.0);
-            (1.0 - a) * Vector::new(1.0, 1.0, 1.0) + a * Vector::new(0.5, 0.7, 1.0)
+            Point::default()
         }
     }
 
-    fn pixel_sample_square(&self) -> Vector {
-        let px = -0.5 + random_between(0.0, 1.0);
-        let py = -0.5 + random_between(0.0, 1.0);
-        (px * self.pixel_delta_u) + (py * self.pixel_delta_v)
+    fn pixel_sample_square(&self) -> (f64, f64) {
+        if self.pixel_centers {
+            return (0.0, 0.0);
+        }
+        let radius = self.pixel_filter.radius();
+        let px = -radius + 2.0 * radius * random_between(0.0, 1.0);
+        let py = -radius + 2.0 * radius * random_between(0.0, 1.0);
+        (px, py)
     }
 
     fn get_ray(&self, x: f64, y: f64) -> Ray {
-        let pixel_center = self.pixel00_loc + (x * self.pixel_delta_u) + (y * self.pixel_delta_v);
-        let pixel_sample = pixel_center + self.pixel_sample_square();
+        let pixel_sample = self.pixel00_loc + (x * self.pixel_delta_u) + (y * self.pixel_delta_v);
 
         let origin = if self.defocus_angle <= 0.0 {
             self.center
@@ -142,7 +1439,14 @@ impl Camera {
             self.defocus_disk_sample()
         };
         let direction = pixel_sample - origin;
-        Ray::new(origin, direction)
+        let shutter = if self.shutter_close > self.shutter_open {
+            random_between(self.shutter_open, self.shutter_close)
+        } else {
+            self.shutter_open
+        };
+        Ray::new_normalized(origin, direction)
+            .with_time(self.time + shutter)
+            .with_primary(true)
     }
 
     fn defocus_disk_sample(&self) -> Point {
@@ -150,3 +1454,1045 @@ impl Camera {
         self.center + (p.x() * self.defocus_disk_u) + (p.y() * self.defocus_disk_v)
     }
 }
+
+/// A record for `hit_into` to overwrite, holding a placeholder material
+/// until then.
+fn scratch_record() -> HitRecord {
+    HitRecord::new(Arc::new(Lambertian::new(Point::default())))
+}
+
+/// The color `Shading::ObjectId` shows `id` as: its hash split into three
+/// channels, so nearby ids look nothing alike.
+fn id_color(id: u32) -> Point {
+    let hash = (u64::from(id) + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    let channel = |shift: u32| f64::from((hash >> shift) as u8) / 255.0;
+    Point::new(channel(56), channel(48), channel(40))
+}
+
+/// The standard error of the mean of `n` samples that add up to `sum` and
+/// whose squares add up to `squares`, 0 for fewer than two.
+fn standard_error(sum: f64, squares: f64, n: u32) -> f64 {
+    if n < 2 {
+        return 0.0;
+    }
+    let n = f64::from(n);
+    let variance = ((squares - sum * sum / n) / (n - 1.0)).max(0.0);
+    (variance / n).sqrt()
+}
+
+/// A `width` by `height` grayscale image of the standard `errors` of every
+/// pixel in row-major order, with gamma as for colors.
+fn error_image(errors: &[f64], width: u32, height: u32) -> GrayImage {
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let error = errors[y as usize * width as usize + x as usize];
+        Luma([(error.sqrt().clamp(0.0, 1.0) * 255.0).round() as u8])
+    })
+}
+
+fn alpha_byte(alpha: f64) -> u8 {
+    (alpha.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Estimates how much longer a render that took `elapsed` to get `fraction`
+/// of the way will take, assuming the rest goes at the same pace.
+fn time_remaining(elapsed: Duration, fraction: f32) -> Option<Duration> {
+    if fraction <= 0.0 {
+        return None;
+    }
+    Some(elapsed.mul_f32((1.0 - fraction).max(0.0) / fraction))
+}
+
+/// The view most tests render: from the origin down -z with a 90 degree
+/// field of view and the focus a unit away, without printing progress.
+#[cfg(test)]
+pub(crate) fn test_init() -> CameraInit {
+    CameraInit {
+        vfov: 90.0,
+        lookat: Point::new(0.0, 0.0, -1.0),
+        vup: Vector::new(0.0, 1.0, 0.0),
+        focus_dist: 1.0,
+        quiet: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn saves_with_encoder_options() {
+    let camera = Camera::new(
+        1.0,
+        4,
+        CameraInit {
+            jpeg_quality: Some(10),
+            png_compression: Some(CompressionType::Best),
+            ..Default::default()
+        },
+    );
+    let image = RgbImage::from_pixel(4, 4, image::Rgb([200, 100, 50]));
+    let dir = std::env::temp_dir();
+
+    for name in ["raytracer_encoder_test.jpg", "raytracer_encoder_test.png"] {
+        let path = dir.join(name);
+        camera.save(&image, &path).unwrap();
+        let loaded = image::open(&path).unwrap().to_rgb8();
+        assert_eq!(loaded.dimensions(), (4, 4));
+        std::fs::remove_file(path).unwrap();
+    }
+}
+
+#[test]
+fn trace_pixel_matches_the_rendered_pixel() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        Arc::new(Lambertian::new(Point::new(0.7, 0.3, 0.2))),
+    )));
+    let init = CameraInit {
+        samples_per_pixel: 8,
+        ..test_init()
+    };
+    let camera = Camera::new(1.0, 8, init);
+    let (colors, _, _) = camera.render_guides(&world);
+    let image = camera.render_to_buffer(&world);
+    for (x, y) in [(4, 4), (0, 0), (6, 3)] {
+        let color = camera.trace_pixel(&world, x, y);
+        assert_eq!(color, colors[y as usize * 8 + x as usize]);
+        assert_eq!(Rgb::from(color.sqrt()), *image.get_pixel(x, y));
+    }
+}
+
+#[test]
+fn sixteen_bit_output_keeps_finer_steps() {
+    let output = std::env::temp_dir().join("raytracer_sixteen_bit_test.png");
+    let init = CameraInit {
+        background: Background::Solid(Point::new(0.25, 0.3, 0.3)),
+        samples_per_pixel: 1,
+        output_depth: OutputDepth::Sixteen,
+        output: Some(output.clone()),
+        quiet: true,
+        ..Default::default()
+    };
+    Camera::new(1.0, 4, init).render(&HittableList::new(None));
+    let loaded = image::open(&output).unwrap();
+    std::fs::remove_file(&output).unwrap();
+    assert_eq!(loaded.color(), image::ColorType::Rgb16);
+    let [r, g, _] = loaded.to_rgb16().get_pixel(1, 2).0;
+    assert_eq!(r, 32767);
+    assert_eq!(g, (0.3f64.sqrt() * 65535.0) as u16);
+}
+
+#[test]
+fn tent_filter_weights_sum_to_one() {
+    let steps = 200;
+    let step = 2.0 * PixelFilter::Tent.radius() / f64::from(steps);
+    let sum: f64 = (0..steps)
+        .flat_map(|i| (0..steps).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            let dx = -PixelFilter::Tent.radius() + (f64::from(i) + 0.5) * step;
+            let dy = -PixelFilter::Tent.radius() + (f64::from(j) + 0.5) * step;
+            PixelFilter::Tent.weight(dx, dy) * step * step
+        })
+        .sum();
+    assert!((sum - 1.0).abs() < 1e-3, "sum was {}", sum);
+    assert_eq!(PixelFilter::Tent.weight(0.0, 0.0), 1.0);
+    assert_eq!(PixelFilter::Tent.weight(1.5, 0.0), 0.0);
+}
+
+#[test]
+fn reports_progress_once_per_row() {
+    use std::{cell::RefCell, rc::Rc};
+
+    let camera = Camera::new(
+        2.0,
+        8,
+        CameraInit {
+            samples_per_pixel: 1,
+            ..Default::default()
+        },
+    );
+    let reported = Rc::new(RefCell::new(Vec::new()));
+    let sink = reported.clone();
+    camera.render_with_progress(
+        &HittableList::new(None),
+        Some(Box::new(move |x| sink.borrow_mut().push(x))),
+    );
+    assert_eq!(*reported.borrow(), [0.25, 0.5, 0.75, 1.0]);
+}
+
+#[test]
+fn total_paths_are_aa_times_path_samples() {
+    let render = |init: CameraInit| {
+        let camera = Camera::new(
+            1.0,
+            2,
+            CameraInit {
+                quiet: true,
+                ..init
+            },
+        );
+        camera
+            .render_with_stats(&HittableList::new(None))
+            .1
+            .primary_rays()
+    };
+    let legacy = CameraInit {
+        samples_per_pixel: 6,
+        ..Default::default()
+    };
+    assert_eq!(render(legacy.clone()), 4 * 6);
+    assert_eq!(
+        render(CameraInit {
+            aa_samples: Some(2),
+            path_samples: Some(3),
+            ..legacy
+        }),
+        4 * 2 * 3
+    );
+}
+
+#[test]
+fn default_gradient_is_the_original_sky() {
+    let sky = Background::default();
+    for direction in [
+        Vector::new(0.0, 1.0, 0.0),
+        Vector::new(0.3, -0.2, 1.0),
+        Vector::new(-1.0, 0.5, 0.0),
+    ] {
+        let ray = Ray::new(Point::default(), direction);
+        let a = 0.5 * (ray.unit_direction().unwrap().y() + 1.0);
+        let original = (1.0 - a) * Vector::new(1.0, 1.0, 1.0) + a * Vector::new(0.5, 0.7, 1.0);
+        assert_eq!(sky.color(&ray), original);
+    }
+}
+
+#[test]
+fn renders_the_same_on_any_number_of_threads() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+    let render = |threads| {
+        let init = CameraInit {
+            samples_per_pixel: 4,
+            threads,
+            ..test_init()
+        };
+        Camera::new(1.0, 16, init).render_to_buffer(&world)
+    };
+    let single = render(1);
+    assert_eq!(single, render(4));
+    assert_eq!(single, render(0));
+}
+
+#[test]
+fn auto_focus_uses_the_distance_to_lookat() {
+    let init = CameraInit {
+        lookfrom: Point::new(1.0, 2.0, 3.0),
+        lookat: Point::new(4.0, 6.0, 3.0),
+        focus_dist: 10.0,
+        ..Default::default()
+    };
+    assert_eq!(init.clone().auto_focus().focus_dist, 5.0);
+    assert_eq!(init.focus_dist, 10.0);
+}
+
+#[test]
+fn pixel_centers_sample_without_jitter() {
+    let camera = Camera::new(
+        2.0,
+        8,
+        CameraInit {
+            samples_per_pixel: 3,
+            pixel_centers: true,
+            ..test_init()
+        },
+    );
+    let image = camera.render_to_buffer(&HittableList::new(None));
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let ray = camera.get_ray(f64::from(x), f64::from(y));
+        let expected = Rgb::from(camera.background.color(&ray).sqrt());
+        assert_eq!(*pixel, expected, "pixel ({}, {})", x, y);
+    }
+}
+
+#[test]
+fn clipping_planes_cut_away_primary_hits() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -2.0),
+        0.5,
+        Arc::new(Lambertian::new(Point::new(1.0, 0.0, 0.0))),
+    )));
+    let center = |near, far| {
+        let camera = Camera::new(
+            1.0,
+            1,
+            CameraInit {
+                vfov: 10.0,
+                lookat: Point::new(0.0, 0.0, -1.0),
+                vup: Vector::new(0.0, 1.0, 0.0),
+                focus_dist: 1.0,
+                samples_per_pixel: 1,
+                quiet: true,
+                pixel_centers: true,
+                background: Background::Solid(Point::new(0.0, 0.0, 1.0)),
+                near,
+                far,
+                ..Default::default()
+            },
+        );
+        *camera.render_to_buffer(&world).get_pixel(0, 0)
+    };
+    let background = Rgb([0, 0, 255]);
+    assert_ne!(center(None, None), background);
+    assert_eq!(center(None, Some(1.0)), background);
+    assert_eq!(center(Some(3.0), None), background);
+}
+
+#[test]
+fn specular_chains_stop_at_the_budget() {
+    use crate::{metal::Metal, quad::Quad};
+
+    let mirror = Arc::new(Metal::new(Point::new(1.0, 1.0, 1.0), 0.0));
+    let mut world = HittableList::new(None);
+    for z in [-1.0, 1.0] {
+        world.add(Box::new(Quad::new(
+            Point::new(-10.0, -10.0, z),
+            Vector::new(20.0, 0.0, 0.0),
+            Vector::new(0.0, 20.0, 0.0),
+            mirror.clone(),
+        )));
+    }
+    let scattered = |max_specular_bounces| {
+        let camera = Camera::new(
+            1.0,
+            1,
+            CameraInit {
+                vfov: 10.0,
+                lookat: Point::new(0.0, 0.0, -1.0),
+                vup: Vector::new(0.0, 1.0, 0.0),
+                focus_dist: 1.0,
+                samples_per_pixel: 1,
+                quiet: true,
+                pixel_centers: true,
+                max_specular_bounces,
+                ..Default::default()
+            },
+        );
+        camera.render_with_stats(&world).1.scattered_rays()
+    };
+    assert_eq!(scattered(None), 49);
+    assert_eq!(scattered(Some(3)), 3);
+}
+
+#[cfg(test)]
+struct NanMaterial;
+
+#[cfg(test)]
+impl crate::material::Material for NanMaterial {
+    fn kind(&self) -> crate::material::MaterialKind {
+        crate::material::MaterialKind::Lambertian
+    }
+
+    fn scatter(&self, _r_in: &Ray, rec: &crate::hittable::HitRecord) -> Option<(Point, Ray)> {
+        Some((
+            Point::new(f64::NAN, 0.0, f64::INFINITY),
+            Ray::new(rec.p, rec.normal),
+        ))
+    }
+}
+
+#[test]
+fn non_finite_samples_are_dropped() {
+    use crate::sphere::Sphere;
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -2.0),
+        0.5,
+        Arc::new(NanMaterial),
+    )));
+    let camera = Camera::new(
+        1.0,
+        1,
+        CameraInit {
+            samples_per_pixel: 1,
+            quiet: true,
+            ..Default::default()
+        },
+    );
+    let ray = Ray::new(Point::default(), Vector::new(0.0, 0.0, -1.0));
+    let mut record = scratch_record();
+    let color = camera.ray_color(&ray, 10, 0, &world, &RenderStats::default(), &mut record);
+    assert!(color.is_finite());
+    assert_eq!(color, Point::default());
+}
+
+#[test]
+fn time_remaining_extrapolates_the_pace_so_far() {
+    let elapsed = Duration::from_secs(30);
+    assert_eq!(time_remaining(elapsed, 0.0), None);
+    assert_eq!(time_remaining(elapsed, 0.25), Some(Duration::from_secs(90)));
+    assert_eq!(time_remaining(elapsed, 1.0), Some(Duration::ZERO));
+}
+
+#[test]
+fn half_sample_passes_combine_like_one_full_render() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+    let camera = |samples_per_pixel| {
+        let init = CameraInit {
+            samples_per_pixel,
+            ..test_init()
+        };
+        Camera::new(1.0, 16, init)
+    };
+
+    let full = camera(64);
+    let expected = full.render_to_buffer(&world);
+    assert_eq!(
+        full.combine_passes(&[full.render_pass(&world, 0)]),
+        expected
+    );
+
+    let half = camera(32);
+    let passes = [half.render_pass(&world, 1), half.render_pass(&world, 2)];
+    assert_ne!(passes[0], passes[1]);
+    let combined = half.combine_passes(&passes);
+    let error = combined
+        .pixels()
+        .zip(expected.pixels())
+        .flat_map(|(a, b)| a.0.into_iter().zip(b.0))
+        .map(|(a, b)| (f64::from(a) - f64::from(b)).abs())
+        .sum::<f64>()
+        / (16.0 * 16.0 * 3.0);
+    assert!(error < 2.0, "mean error {}", error);
+}
+
+#[test]
+fn luminance_weights_green_most() {
+    let green = RgbImage::from_pixel(2, 2, Rgb([0, 255, 0]));
+    let gray = luminance(&green);
+    assert_eq!(gray.dimensions(), (2, 2));
+    assert_eq!(gray.get_pixel(1, 1).0, [182]);
+    let white = RgbImage::from_pixel(1, 1, Rgb([255, 255, 255]));
+    assert_eq!(luminance(&white).get_pixel(0, 0).0, [255]);
+}
+
+#[test]
+fn vignette_darkens_the_corners_only() {
+    let render = |vignette| {
+        let init = CameraInit {
+            background: Background::Solid(Point::new(0.25, 0.25, 0.25)),
+            samples_per_pixel: 1,
+            quiet: true,
+            vignette,
+            ..Default::default()
+        };
+        Camera::new(1.0, 5, init).render_to_buffer(&HittableList::new(None))
+    };
+    let plain = render(None);
+    let vignetted = render(Some(0.5));
+    assert_eq!(plain.get_pixel(2, 2).0, [127; 3]);
+    assert_eq!(vignetted.get_pixel(2, 2), plain.get_pixel(2, 2));
+
+    // The corner pixel's center is 0.8 of the way to the corner.
+    let expected = (0.5 * (1.0 - 0.5 * 0.8 * 0.8) * 255.0) as u8;
+    assert_eq!(vignetted.get_pixel(0, 0).0, [expected; 3]);
+    assert_eq!(vignetted.get_pixel(4, 4).0, [expected; 3]);
+}
+
+#[test]
+fn each_stop_of_exposure_doubles_linear_values() {
+    let render = |exposure| {
+        let init = CameraInit {
+            background: Background::Solid(Point::new(0.125, 0.125, 0.125)),
+            samples_per_pixel: 1,
+            quiet: true,
+            exposure,
+            ..Default::default()
+        };
+        let image = Camera::new(1.0, 1, init).render_to_buffer(&HittableList::new(None));
+        image.get_pixel(0, 0).0[0]
+    };
+    let display = |linear: f64| (linear.sqrt() * 255.0) as u8;
+    assert_eq!(render(0.0), display(0.125));
+    assert_eq!(render(1.0), display(0.25));
+    assert_eq!(render(2.0), display(0.5));
+    assert_eq!(render(-1.0), display(0.0625));
+}
+
+#[test]
+fn auto_exposure_brings_uniform_images_to_the_key() {
+    let render = |brightness, exposure| {
+        let init = CameraInit {
+            background: Background::Solid(Point::new(brightness, brightness, brightness)),
+            samples_per_pixel: 1,
+            quiet: true,
+            exposure,
+            auto_exposure: true,
+            ..Default::default()
+        };
+        let image = Camera::new(1.0, 4, init).render_to_buffer(&HittableList::new(None));
+        image.get_pixel(0, 0).0[0]
+    };
+    let key = (AUTO_EXPOSURE_KEY.sqrt() * 255.0) as u8;
+    for brightness in [0.02, 0.5, 5.0, 500.0] {
+        assert!(render(brightness, 0.0).abs_diff(key) <= 1, "{}", brightness);
+    }
+    // Exposure still applies on top.
+    let brighter = ((2.0 * AUTO_EXPOSURE_KEY).sqrt() * 255.0) as u8;
+    assert!(render(5.0, 1.0).abs_diff(brighter) <= 1);
+}
+
+#[test]
+fn heatmap_counts_the_boxes_each_pixel_passes() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)));
+    let mut inner = HittableList::new(None);
+    inner.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -2.0),
+        0.5,
+        mat.clone(),
+    )));
+    let mut world = HittableList::new(None);
+    world.add(Box::new(inner));
+    world.add(Box::new(Sphere::new(Point::new(0.0, 0.0, -4.0), 0.5, mat)));
+
+    let init = CameraInit {
+        shading: Shading::BvhHeatmap,
+        ..test_init()
+    };
+    let image = Camera::new(1.0, 9, init).render_to_buffer(&world);
+    // The world's box, the inner list's, and both spheres'.
+    let center = Rgb::from(Point::new(0.25, 0.0, 0.75).sqrt());
+    assert_eq!(image.get_pixel(4, 4), &center);
+    assert_eq!(image.get_pixel(0, 0), &Rgb([0, 0, 0]));
+}
+
+#[test]
+fn cost_heatmap_is_cheap_where_rays_miss_the_scene() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)));
+    let mut world = HittableList::new(None);
+    for i in 0..8 {
+        let x = f64::from(i) * 0.25 - 1.0;
+        world.add(Box::new(Sphere::new(
+            Point::new(x, -0.75, -2.0),
+            0.1,
+            mat.clone(),
+        )));
+    }
+    world.sort_along_dominant_axis();
+
+    let init = CameraInit {
+        shading: Shading::CostHeatmap,
+        ..test_init()
+    };
+    let camera = Camera::new(1.0, 8, init);
+    let ray_t = Interval::new_set_interval(0.001, f64::MAX);
+    let cost = |x, y| {
+        let ray = camera.get_ray(f64::from(x), f64::from(y));
+        let before = stats::intersection_tests();
+        world.hit(&ray, &ray_t);
+        stats::intersection_tests() - before
+    };
+    // Looking up, away from the spheres, only the list's box is tested.
+    assert_eq!(cost(4, 0), 1);
+    assert!(cost(2, 5) > 1);
+
+    let image = camera.render_to_buffer(&world);
+    let heat = |n: f64| Rgb::from(Point::new(n / 256.0, 0.0, 1.0 - n / 256.0).sqrt());
+    assert_eq!(image.get_pixel(4, 0), &heat(1.0));
+}
+
+#[test]
+fn explicit_sizes_are_rendered_exactly() {
+    let init = CameraInit {
+        samples_per_pixel: 1,
+        ..test_init()
+    };
+    assert_eq!(
+        Camera::new(16.0 / 9.0, 720, init.clone()).dimensions(),
+        (720, 405)
+    );
+
+    let camera = Camera::with_size(721, 406, init.clone());
+    assert_eq!(camera.dimensions(), (721, 406));
+    let pixel_aspect = camera.pixel_delta_u.len() / camera.pixel_delta_v.len();
+    assert!((pixel_aspect - 1.0).abs() < 1e-12);
+
+    let image = Camera::with_size(7, 3, init).render_to_buffer(&HittableList::new(None));
+    assert_eq!(image.dimensions(), (7, 3));
+}
+
+#[test]
+fn renders_into_rgb_and_rgba_slices() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+    let init = CameraInit {
+        samples_per_pixel: 4,
+        ..test_init()
+    };
+    let camera = Camera::with_size(8, 6, init);
+    let expected = camera.render_to_buffer(&world);
+
+    let mut rgb = vec![0; 8 * 6 * 3];
+    camera.render_into(&world, &mut rgb, 8, 6).unwrap();
+    assert_eq!(rgb, expected.as_raw().as_slice());
+
+    let mut rgba = vec![0; 8 * 6 * 4];
+    camera.render_into(&world, &mut rgba, 8, 6).unwrap();
+    for (pixel, expected) in rgba.chunks_exact(4).zip(expected.pixels()) {
+        assert_eq!(pixel, [expected.0[0], expected.0[1], expected.0[2], 255]);
+    }
+
+    let error = camera.render_into(&world, &mut rgb[1..], 8, 6).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert!(camera.render_into(&world, &mut rgb, 6, 8).is_err());
+}
+
+#[test]
+fn background_is_transparent_with_alpha_background() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+    let camera = |alpha_background| {
+        let init = CameraInit {
+            samples_per_pixel: 16,
+            alpha_background,
+            ..test_init()
+        };
+        Camera::new(1.0, 16, init)
+    };
+
+    let (opaque, _) = camera(false).render_rgba(&world);
+    assert!(opaque.pixels().all(|p| p.0[3] == 255));
+
+    let (image, _) = camera(true).render_rgba(&world);
+    assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+    assert_eq!(image.get_pixel(8, 8).0[3], 255);
+    // A pixel on the sphere's edge is partly covered.
+    let edge = (0..16).map(|x| image.get_pixel(x, 8).0[3]);
+    assert!(
+        edge.clone().any(|a| a > 0 && a < 255),
+        "{:?}",
+        edge.collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn cancelling_stops_the_render_early() {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let camera = Camera::new(
+        1.0,
+        8,
+        CameraInit {
+            samples_per_pixel: 2,
+            cancel: Some(cancel.clone()),
+            ..test_init()
+        },
+    );
+    let world = HittableList::new(None);
+    let (image, stats) = camera.render_with_stats(&world);
+    assert_eq!(stats.primary_rays(), 8 * 8 * 2);
+    assert!(image.pixels().all(|p| p.0 != [0, 0, 0]));
+
+    cancel.store(true, Ordering::Relaxed);
+    let (image, stats) = camera.render_with_stats(&world);
+    assert_eq!(stats.primary_rays(), 0);
+    assert!(image.pixels().all(|p| p.0 == [0, 0, 0]));
+}
+
+#[test]
+fn tiny_ray_epsilon_lets_bounces_hit_their_own_surface() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    // On ground a million units across, rounding leaves some hit points
+    // just under the surface.
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, -1e6, 0.0),
+        1e6,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+    let self_hits = |ray_epsilon: Option<f64>| {
+        let camera = Camera::new(
+            1.0,
+            1,
+            CameraInit {
+                ray_epsilon,
+                ..Default::default()
+            },
+        );
+        (0..100)
+            .filter(|&i| {
+                let x = f64::from(i) * 37.3 + 0.123;
+                let ray = Ray::new(Point::new(x, 10.0, 0.3 * x), Vector::new(0.0, -1.0, 0.1));
+                let record = world.hit(&ray, &camera.bounce_interval()).unwrap();
+                let bounce = Ray::new(record.p, record.normal + Vector::new(0.3, 0.0, 0.0));
+                world.hit(&bounce, &camera.bounce_interval()).is_some()
+            })
+            .count()
+    };
+
+    assert_eq!(self_hits(None), 0);
+    assert!(self_hits(Some(1e-12)) > 0);
+}
+
+#[test]
+fn previews_are_a_quarter_of_the_size_with_the_same_view() {
+    let init = CameraInit {
+        samples_per_pixel: 100,
+        ..test_init()
+    };
+    let camera = Camera::with_size(64, 36, init.clone());
+    let preview = camera.preview_camera();
+    assert_eq!(preview.dimensions(), (16, 9));
+    // Saved with the preview's own limits.
+    assert_eq!(preview.settings.max_depth, Some(8));
+    assert_eq!(preview.settings.aa_samples, Some(16));
+    assert_eq!(
+        Camera::with_size(2, 2, init).preview_camera().dimensions(),
+        (1, 1)
+    );
+
+    let (image, stats) = preview.render_with_stats(&HittableList::new(None));
+    assert_eq!(image.dimensions(), (16, 9));
+    assert_eq!(stats.primary_rays(), 16 * 9 * 16);
+    // The corners of the frame stay where they were.
+    let corner = |camera: &Camera| camera.get_ray(-0.5, -0.5).direction();
+    assert!((corner(&camera) - corner(&preview)).len() < 1e-12);
+}
+
+#[test]
+fn ray_times_fall_within_the_shutter_interval() {
+    let camera = Camera::new(
+        1.0,
+        4,
+        CameraInit {
+            time: 2.0,
+            shutter_open: 0.25,
+            shutter_close: 0.75,
+            ..test_init()
+        },
+    );
+    let times: Vec<_> = (0..1000).map(|_| camera.get_ray(1.0, 2.0).time()).collect();
+    assert!(times.iter().all(|t| (2.25..2.75).contains(t)));
+    let (min, max) = times.iter().fold((f64::MAX, f64::MIN), |(min, max), t| {
+        (min.min(*t), max.max(*t))
+    });
+    assert!(min < 2.3 && max > 2.7, "times from {} to {}", min, max);
+
+    let instant = Camera::new(1.0, 4, CameraInit::default());
+    assert_eq!(instant.get_ray(1.0, 2.0).time(), 0.0);
+}
+
+#[test]
+fn ambient_occlusion_darkens_where_a_sphere_meets_the_ground() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)));
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        mat.clone(),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, -100.5, -1.0),
+        100.0,
+        mat,
+    )));
+
+    let shading = Shading::AmbientOcclusion {
+        samples: 256,
+        radius: 1.0,
+    };
+    let camera = Camera::new(
+        1.0,
+        1,
+        CameraInit {
+            shading,
+            ..Default::default()
+        },
+    );
+    // Straight down onto the ground beside the sphere and far from it.
+    let ground_at = |x: f64| {
+        let ray = Ray::new(Point::new(x, -0.3, -1.0), Vector::new(0.0, -1.0, 0.0));
+        camera.ambient_occlusion(&ray, &world, 256, 1.0).x()
+    };
+    let (contact, open) = (ground_at(0.55), ground_at(3.0));
+    assert!(
+        contact < 0.9 * open,
+        "{} near the sphere, {} away",
+        contact,
+        open
+    );
+    assert!(open > 0.95);
+    let sky = Ray::new(Point::default(), Vector::new(0.0, 1.0, 0.0));
+    assert_eq!(
+        camera.ambient_occlusion(&sky, &world, 256, 1.0),
+        Point::new(1.0, 1.0, 1.0)
+    );
+}
+
+#[test]
+fn sky_sampling_cuts_noise_under_a_bright_zenith() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    let gray = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)));
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, -100.5, -1.0),
+        100.0,
+        gray.clone(),
+    )));
+    world.add(Box::new(Sphere::new(Point::new(0.0, 0.0, -1.0), 0.5, gray)));
+    let render = |samples_per_pixel, sky_sampling| {
+        let init = CameraInit {
+            samples_per_pixel,
+            sky_sampling,
+            background: Background::Gradient {
+                top: Point::new(2.0, 2.0, 2.0),
+                bottom: Point::new(0.02, 0.02, 0.02),
+                power: 16.0,
+            },
+            ..test_init()
+        };
+        Camera::new(1.0, 16, init).render_to_buffer(&world)
+    };
+    // Against a far less noisy render, 16 samples a pixel toward the sky
+    // land much closer than 16 cosine-weighted ones.
+    let reference = render(1024, true);
+    let error = |image: RgbImage| {
+        image
+            .iter()
+            .zip(reference.iter())
+            .map(|(a, b)| (f64::from(*a) - f64::from(*b)).powi(2))
+            .sum::<f64>()
+    };
+    let before = error(render(16, false));
+    let after = error(render(16, true));
+    assert!(after < 0.6 * before, "before {} after {}", before, after);
+}
+
+#[test]
+fn settings_come_from_the_environment() {
+    use std::collections::HashMap;
+
+    let output = std::env::temp_dir().join("raytracer_env_test.png");
+    let mut vars = HashMap::from([
+        ("RT_SAMPLES", OsString::from("7")),
+        ("RT_WIDTH", OsString::from("40")),
+        ("RT_OUTPUT", output.clone().into_os_string()),
+    ]);
+    let init = CameraInit::default()
+        .with_vars(|name| vars.get(name).cloned())
+        .unwrap();
+    assert_eq!(init.samples_per_pixel, 7);
+    assert_eq!(init.output.as_deref(), Some(output.as_path()));
+    assert_eq!(init.max_depth, None);
+    assert_eq!(Camera::new(2.0, 1000, init).dimensions(), (40, 20));
+
+    vars.insert("RT_MAX_DEPTH", OsString::from("deep"));
+    let error = CameraInit::default()
+        .with_vars(|name| vars.get(name).cloned())
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert!(
+        error.to_string().starts_with("RT_MAX_DEPTH: \"deep\""),
+        "{}",
+        error
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn non_unicode_settings_are_kept_or_refused() {
+    use std::os::unix::ffi::OsStringExt;
+
+    let name = OsString::from_vec(b"caf\xe9.png".to_vec());
+    let init = CameraInit::default()
+        .with_vars(|var| (var == "RT_OUTPUT").then(|| name.clone()))
+        .unwrap();
+    assert_eq!(init.output.as_deref(), Some(Path::new(&name)));
+
+    let error = CameraInit::default()
+        .with_vars(|var| (var == "RT_SAMPLES").then(|| name.clone()))
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert!(error.to_string().ends_with("not unicode"), "{}", error);
+}
+
+#[test]
+fn previews_are_saved_beside_the_output() {
+    let dir = std::env::temp_dir().join("raytracer-preview");
+    std::fs::create_dir_all(&dir).unwrap();
+    let init = CameraInit {
+        samples_per_pixel: 1,
+        output: Some(dir.join("scene.png")),
+        ..test_init()
+    };
+    let camera = Camera::with_size(16, 8, init);
+    camera.preview(&HittableList::new(None)).unwrap();
+    let saved = image::open(dir.join("scene.preview.png")).unwrap();
+    assert_eq!((saved.width(), saved.height()), (4, 2));
+    assert_eq!(
+        Camera::new(1.0, 4, CameraInit::default()).preview_path(),
+        Path::new("fractal.preview.png")
+    );
+
+    let unwritable = CameraInit {
+        output: Some(dir.join("missing").join("scene.png")),
+        quiet: true,
+        ..Default::default()
+    };
+    let camera = Camera::with_size(4, 4, unwritable);
+    assert!(camera.preview(&HittableList::new(None)).is_err());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn focus_peaking_flags_the_focus_plane() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    // Its nearest point dead ahead is exactly one unit away.
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.5),
+        0.5,
+        Arc::new(Lambertian::new(Point::new(0.7, 0.3, 0.2))),
+    )));
+    let peaking = |focus_dist| {
+        let init = CameraInit {
+            focus_dist,
+            samples_per_pixel: 4,
+            shading: Shading::FocusPeaking { tolerance: 0.01 },
+            ..test_init()
+        };
+        let (colors, _, _, _) = Camera::new(1.0, 9, init).render_linear(&world, &mut |_| {});
+        colors
+    };
+    let gray = |c: Point| c.x() == c.y() && c.y() == c.z();
+
+    let in_focus = peaking(1.0);
+    let center = in_focus[4 * 9 + 4];
+    assert!(
+        center.y() > center.x() && center.x() == center.z(),
+        "{:?}",
+        center
+    );
+    assert!(gray(in_focus[0]));
+    assert!(gray(peaking(3.0)[4 * 9 + 4]));
+}
+
+#[test]
+fn variance_output_shows_noise_at_edges() {
+    use crate::{diffuse_light::DiffuseLight, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        Arc::new(DiffuseLight::new(Point::new(1.0, 1.0, 1.0), 1.0)),
+    )));
+    let dir = std::env::temp_dir().join("raytracer-variance-output");
+    std::fs::create_dir_all(&dir).unwrap();
+    let variance = dir.join("variance.png");
+    let init = CameraInit {
+        samples_per_pixel: 16,
+        background: Background::Solid(Point::default()),
+        variance_output: Some(variance.clone()),
+        ..test_init()
+    };
+    Camera::new(1.0, 9, init)
+        .render_to(&world, dir.join("color.png"))
+        .unwrap();
+
+    let image = image::open(&variance).unwrap().into_luma8();
+    // Flat light, flat background, and the sphere's rim.
+    assert_eq!(image.get_pixel(4, 4).0, [0]);
+    assert_eq!(image.get_pixel(0, 0).0, [0]);
+    assert!(
+        image.get_pixel(7, 4).0[0] > 64,
+        "{:?}",
+        image.get_pixel(7, 4)
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn object_id_shading_colors_tagged_objects() {
+    use crate::{id_tag::IdTag, lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)));
+    let sphere = |x| Box::new(Sphere::new(Point::new(x, 0.0, -1.0), 0.3, mat.clone()));
+    let mut world = HittableList::new(None);
+    world.add(Box::new(IdTag::new(0, sphere(-0.6))));
+    world.add(Box::new(IdTag::new(1, sphere(0.6))));
+    let init = CameraInit {
+        shading: Shading::ObjectId,
+        ..test_init()
+    };
+    let image = Camera::new(1.0, 9, init).render_to_buffer(&world);
+    let (left, right) = (image.get_pixel(1, 4), image.get_pixel(7, 4));
+    assert_eq!(*left, Rgb::from(id_color(0).sqrt()));
+    assert_eq!(*right, Rgb::from(id_color(1).sqrt()));
+    assert_ne!(left, right);
+    assert_ne!(left, &Rgb([0, 0, 0]));
+    assert_eq!(image.get_pixel(4, 0), &Rgb([0, 0, 0]));
+}
+
+#[test]
+fn alpha_backgrounds_trace_each_camera_ray_once() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -2.0),
+        1.0,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+    let tests = |alpha_background| {
+        let init = CameraInit {
+            samples_per_pixel: 4,
+            max_depth: Some(1),
+            pixel_centers: true,
+            alpha_background,
+            ..test_init()
+        };
+        let camera = Camera::new(1.0, 1, init);
+        let before = stats::intersection_tests();
+        camera.trace_pixel(&world, 0, 0);
+        stats::intersection_tests() - before
+    };
+    assert_eq!(tests(true), tests(false));
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use image::ImageResult;
+
+use crate::{
+    point::{Point, Vector},
+    texture::{ImageTexture, Texture, WrapMode},
+};
+
+/// A skybox made of six images, in the order +x, -x, +y, -y, +z, -z, laid
+/// out the usual way for cube maps: seen from the inside, with +y up on the
+/// side faces.
+#[derive(Debug, PartialEq)]
+pub struct CubeMap {
+    faces: [ImageTexture; 6],
+}
+
+impl CubeMap {
+    /// Faces are sampled bilinearly and clamped at the edges, so lookups near
+    /// a seam don't bleed in the opposite edge of the same face.
+    pub fn new(faces: [ImageTexture; 6]) -> Self {
+        Self {
+            faces: faces.map(|x| x.with_wrap(WrapMode::Clamp)),
+        }
+    }
+
+    pub fn load(paths: [impl AsRef<Path>; 6]) -> ImageResult<Self> {
+        let mut faces = Vec::with_capacity(6);
+        for path in paths {
+            faces.push(ImageTexture::load(path, true)?);
+        }
+        Ok(Self::new(
+            faces.try_into().unwrap_or_else(|_| unreachable!()),
+        ))
+    }
+
+    /// The face pointed at by the largest component of `direction`, and the
+    /// in-face coordinates with `(0, 0)` at the image's top-left corner.
+    fn face(direction: &Vector) -> (usize, f64, f64) {
+        let (x, y, z) = (direction.x(), direction.y(), direction.z());
+        let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+        let (face, s, t, major) = if ax >= ay && ax >= az {
+            if x > 0.0 {
+                (0, -z, -y, ax)
+            } else {
+                (1, z, -y, ax)
+            }
+        } else if ay >= az {
+            if y > 0.0 {
+                (2, x, z, ay)
+            } else {
+                (3, x, -z, ay)
+            }
+        } else if z > 0.0 {
+            (4, x, -y, az)
+        } else {
+            (5, -x, -y, az)
+        };
+        (face, 0.5 * (s / major + 1.0), 0.5 * (t / major + 1.0))
+    }
+
+    pub fn color(&self, direction: &Vector) -> Point {
+        if direction.near_zero() {
+            return Point::default();
+        }
+        let (face, s, t) = Self::face(direction);
+        self.faces[face].value(s, 1.0 - t, direction)
+    }
+}
+
+#[test]
+fn picks_the_face_along_the_dominant_axis() {
+    use image::RgbImage;
+
+    let faces: [ImageTexture; 6] = std::array::from_fn(|i| {
+        let image = RgbImage::from_fn(2, 2, |x, y| image::Rgb([i as u8 * 10, x as u8, y as u8]));
+        ImageTexture::new(image, false)
+    });
+    let cube = CubeMap::new(faces);
+    let lookup = |x, y, z| {
+        let c = cube.color(&Vector::new(x, y, z)).sqrt();
+        [c.x(), c.y(), c.z()].map(|x| (x * 255.0).round() as u8)
+    };
+    let face = |x, y, z| lookup(x, y, z)[0] / 10;
+
+    assert_eq!(face(1.0, 0.2, -0.3), 0);
+    assert_eq!(face(-1.0, 0.2, 0.3), 1);
+    assert_eq!(face(0.1, 2.0, 0.3), 2);
+    assert_eq!(face(0.1, -2.0, 0.3), 3);
+    assert_eq!(face(0.1, 0.2, 0.5), 4);
+    assert_eq!(face(0.1, 0.2, -0.5), 5);
+
+    // Up and to the right on +z lands in the top-right texel.
+    let [_, column, row] = lookup(0.5, 0.5, 1.0);
+    assert_eq!((column, row), (1, 0));
+}
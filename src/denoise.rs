@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use image::{ImageBuffer, ImageResult, Rgb, Rgb32FImage, RgbImage};
+
+use crate::point::{Point, Vector};
+
+/// How far apart, per channel of each buffer, two pixels can be before the
+/// filter stops blending them.
+const COLOR_SIGMA: f64 = 0.25;
+const NORMAL_SIGMA: f64 = 0.3;
+const ALBEDO_SIGMA: f64 = 0.1;
+
+/// Smooths `color`, a row-major image `width` pixels wide, with a
+/// cross-bilateral filter: each pixel becomes a weighted average of its
+/// neighbors within about `2 * strength` pixels, where neighbors that differ
+/// in color, or in the optional per-pixel `normals` and `albedo`, weigh
+/// less.
+///
+/// The color alone can't tell noise from detail, so without the normal and
+/// albedo buffers edges and textures blur along with the noise; with them,
+/// only pixels on the same surface with the same base color are blended.
+pub fn bilateral(
+    color: &[Point],
+    width: usize,
+    strength: f64,
+    normals: Option<&[Vector]>,
+    albedo: Option<&[Point]>,
+) -> Vec<Point> {
+    if width == 0 || strength <= 0.0 {
+        return color.to_vec();
+    }
+    let height = color.len() / width;
+    let radius = (2.0 * strength).ceil() as isize;
+    let falloff =
+        |distance_squared: f64, sigma: f64| (-distance_squared / (2.0 * sigma * sigma)).exp();
+
+    (0..color.len())
+        .map(|i| {
+            let (x, y) = ((i % width) as isize, (i / width) as isize);
+            let mut sum = Point::default();
+            let mut total = 0.0;
+            for ny in (y - radius).max(0)..=(y + radius).min(height as isize - 1) {
+                for nx in (x - radius).max(0)..=(x + radius).min(width as isize - 1) {
+                    let j = ny as usize * width + nx as usize;
+                    let (dx, dy) = ((nx - x) as f64, (ny - y) as f64);
+                    let mut weight = falloff(dx * dx + dy * dy, strength)
+                        * falloff((color[j] - color[i]).len_squared(), COLOR_SIGMA);
+                    if let Some(normals) = normals {
+                        weight *= falloff((normals[j] - normals[i]).len_squared(), NORMAL_SIGMA);
+                    }
+                    if let Some(albedo) = albedo {
+                        weight *= falloff((albedo[j] - albedo[i]).len_squared(), ALBEDO_SIGMA);
+                    }
+                    sum += weight * color[j];
+                    total += weight;
+                }
+            }
+            (sum / total).unwrap_or(color[i])
+        })
+        .collect()
+}
+
+/// Writes `pixels`, a row-major image `width` pixels wide, as a 32-bit float
+/// OpenEXR file, keeping values outside `[0, 1]` for tools that expect
+/// linear data.
+pub fn write_exr(path: impl AsRef<Path>, pixels: &[Point], width: usize) -> ImageResult<()> {
+    let height = pixels.len().checked_div(width).unwrap_or(0);
+    let image: Rgb32FImage = ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
+        let p = pixels[y as usize * width + x as usize];
+        Rgb([p.x() as f32, p.y() as f32, p.z() as f32])
+    });
+    image.save(path)
+}
+
+/// Reads an image written by `write_exr`, or any other, as linear pixels
+/// in row-major order along with its width.
+pub fn read_exr(path: impl AsRef<Path>) -> ImageResult<(Vec<Point>, usize)> {
+    let image = image::open(path)?.to_rgb32f();
+    let pixels = image
+        .pixels()
+        .map(|Rgb([r, g, b])| Point::new(f64::from(*r), f64::from(*g), f64::from(*b)))
+        .collect();
+    Ok((pixels, image.width() as usize))
+}
+
+/// Writes the buffers an external denoiser such as OpenImageDenoise is
+/// guided by into `dir`, as `color.exr`, `normal.exr` and `albedo.exr`.
+pub fn export_guides(
+    dir: impl AsRef<Path>,
+    color: &[Point],
+    normals: &[Vector],
+    albedo: &[Point],
+    width: usize,
+) -> ImageResult<()> {
+    let dir = dir.as_ref();
+    write_exr(dir.join("color.exr"), color, width)?;
+    write_exr(dir.join("normal.exr"), normals, width)?;
+    write_exr(dir.join("albedo.exr"), albedo, width)
+}
+
+/// Gamma-corrects the externally `denoised` version of `color` into the
+/// final image, keeping the original pixel wherever the denoiser produced
+/// something that isn't finite or the buffer falls short.
+pub fn merge_denoised(color: &[Point], denoised: &[Point], width: usize) -> RgbImage {
+    let height = color.len().checked_div(width).unwrap_or(0);
+    ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
+        let i = y as usize * width + x as usize;
+        let pixel = match denoised.get(i) {
+            Some(p) if p.is_finite() => *p,
+            _ => color[i],
+        };
+        Rgb::from(pixel.sqrt())
+    })
+}
+
+#[test]
+fn smooths_noise_but_keeps_surface_edges() {
+    let width = 8;
+    let color: Vec<_> = (0..64)
+        .map(|i| {
+            let base = if i % width < 4 { 0.2 } else { 0.6 };
+            let noise = if (i + i / width) % 2 == 0 {
+                0.05
+            } else {
+                -0.05
+            };
+            Point::new(base + noise, base + noise, base + noise)
+        })
+        .collect();
+    let normals: Vec<_> = (0..64)
+        .map(|i| {
+            if i % width < 4 {
+                Vector::new(1.0, 0.0, 0.0)
+            } else {
+                Vector::new(0.0, 1.0, 0.0)
+            }
+        })
+        .collect();
+
+    let denoised = bilateral(&color, width, 1.0, Some(&normals), None);
+    let error = |image: &[Point], i: usize| {
+        let base = if i % width < 4 { 0.2 } else { 0.6 };
+        (image[i].x() - base).abs()
+    };
+    for i in 0..64 {
+        assert!(error(&denoised, i) < error(&color, i), "pixel {}", i);
+        assert!(error(&denoised, i) < 0.05, "pixel {}", i);
+    }
+}
+
+#[test]
+fn exr_round_trips_linear_values() {
+    let width = 3;
+    let pixels: Vec<_> = (0..6)
+        .map(|i| Point::new(f64::from(i) * 0.5, -0.25, 4.0))
+        .collect();
+    let path = std::env::temp_dir().join("raytracer_denoise_test.exr");
+    write_exr(&path, &pixels, width).unwrap();
+    let (read, read_width) = read_exr(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(read_width, width);
+    assert_eq!(read, pixels);
+
+    let mut denoised = read;
+    denoised[1] = Point::new(f64::NAN, 0.0, 0.0);
+    let merged = merge_denoised(&pixels, &denoised, width);
+    assert_eq!(merged.dimensions(), (3, 2));
+    assert_eq!(merged.get_pixel(1, 0), &Rgb::from(pixels[1].sqrt()));
+}
@@ -1,9 +1,10 @@
 use crate::{
     hittable::HitRecord,
-    material::Material,
-    point::{dot, reflect, refract, Point},
+    material::{Material, MaterialKind, ScatterRecord, Scattered},
+    point::Point,
     ray::Ray,
-    utils::random_between,
+    scene::Json,
+    utils::{random_between, schlick},
 };
 
 pub struct Dielectric {
@@ -14,15 +15,28 @@ impl Dielectric {
     pub fn new(ir: f64) -> Self {
         Self { ir }
     }
-
-    fn reflectance(cosine: f64, ref_idx: f64) -> f64 {
-        let mut r0 = (1.0 - ref_idx) / (1.0 + ref_idx);
-        r0 = r0 * r0;
-        r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
-    }
 }
 
 impl Material for Dielectric {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::Dielectric
+    }
+
+    fn is_specular(&self) -> bool {
+        true
+    }
+
+    /// Always a single reflected or refracted ray: glass has no density to
+    /// mix with light sampling, so the camera follows the ray as is and
+    /// light reaches it only through the bounces that come after.
+    fn scatter_record(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let (attenuation, ray) = self.scatter(r_in, rec)?;
+        Some(ScatterRecord {
+            attenuation,
+            scattered: Scattered::Ray(ray),
+        })
+    }
+
     fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
         let refraction_ratio = if rec.front_face {
             1.0 / self.ir
@@ -30,18 +44,41 @@ impl Material for Dielectric {
             self.ir
         };
 
-        let unit_direction = r_in.direction().unit()?;
-        let cos_theta = dot(&-unit_direction, &rec.normal).min(1.0);
-        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let unit_direction = r_in.unit_direction()?;
+        let cos_theta = (-unit_direction).dot(&rec.normal).min(1.0);
 
-        let direction = if refraction_ratio * sin_theta > 1.0
-            || Dielectric::reflectance(cos_theta, refraction_ratio) > random_between(0.0, 1.0)
-        {
-            reflect(&unit_direction, &rec.normal)
-        } else {
-            refract(&unit_direction, &rec.normal, refraction_ratio)
+        let direction = match unit_direction.refract(&rec.normal, refraction_ratio) {
+            Some(refracted) if schlick(cos_theta, refraction_ratio) <= random_between(0.0, 1.0) => {
+                refracted
+            }
+            _ => unit_direction.reflect(&rec.normal),
         };
 
         Some((Point::new(1.0, 1.0, 1.0), Ray::new(rec.p, direction)))
     }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "dielectric",
+            [("refraction_index", self.ir.into())],
+        ))
+    }
+}
+
+#[test]
+fn glass_scatters_as_a_specular_ray() {
+    use std::sync::Arc;
+
+    let glass = Arc::new(Dielectric::new(1.5));
+    assert!(glass.is_specular());
+
+    let mut rec = HitRecord::new(glass.clone());
+    rec.normal = Point::new(0.0, 1.0, 0.0);
+    rec.front_face = true;
+    let r_in = Ray::new(Point::new(0.0, 1.0, 1.0), Point::new(0.0, -1.0, -1.0));
+    for _ in 0..100 {
+        let scatter = glass.scatter_record(&r_in, &rec).unwrap();
+        assert_eq!(scatter.attenuation, Point::new(1.0, 1.0, 1.0));
+        assert!(matches!(scatter.scattered, Scattered::Ray(_)));
+    }
 }
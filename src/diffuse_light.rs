@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use radians::Deg;
+
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind},
+    point::Point,
+    ray::Ray,
+    scene::Json,
+    texture::{SolidColor, Texture},
+};
+
+pub struct DiffuseLight {
+    emit: Arc<dyn Texture>,
+    intensity: f64,
+}
+
+impl DiffuseLight {
+    /// Emits `color` scaled by `intensity`. The result is radiance, not a
+    /// display color, so it is free to exceed 1.
+    pub fn new(color: Point, intensity: f64) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(color)), intensity)
+    }
+
+    /// Emits `emit`, looked up at the hit's surface coordinates, scaled by
+    /// `intensity`.
+    pub fn with_texture(emit: Arc<dyn Texture>, intensity: f64) -> Self {
+        Self { emit, intensity }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::DiffuseLight
+    }
+
+    fn scatter(&self, _r_in: &Ray, _rec: &HitRecord) -> Option<(Point, Ray)> {
+        None
+    }
+
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Point {
+        if rec.front_face {
+            self.emit.value(rec.u, rec.v, &rec.p) * self.intensity
+        } else {
+            Point::default()
+        }
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "diffuse_light",
+            [
+                ("emit", self.emit.to_json()?),
+                ("intensity", self.intensity.into()),
+            ],
+        ))
+    }
+}
+
+/// A light that only emits within a cone around `direction`.
+pub struct SpotLight {
+    emit: Point,
+    direction: Point,
+    cos_inner: f64,
+    cos_outer: f64,
+}
+
+impl SpotLight {
+    /// Emits at full strength up to `inner` degrees off `direction`, fading
+    /// smoothly to nothing at `outer` degrees.
+    pub fn new(color: Point, intensity: f64, direction: Point, inner: f64, outer: f64) -> Self {
+        let outer = outer.max(inner);
+        Self {
+            emit: color * intensity,
+            direction: direction.unit().unwrap_or_default(),
+            cos_inner: Deg::new(inner).cos(),
+            cos_outer: Deg::new(outer).cos(),
+        }
+    }
+
+    fn falloff(&self, cos_angle: f64) -> f64 {
+        if cos_angle >= self.cos_inner {
+            1.0
+        } else if cos_angle <= self.cos_outer {
+            0.0
+        } else {
+            let t = (cos_angle - self.cos_outer) / (self.cos_inner - self.cos_outer);
+            t * t * (3.0 - 2.0 * t)
+        }
+    }
+}
+
+impl Material for SpotLight {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::SpotLight
+    }
+
+    fn scatter(&self, _r_in: &Ray, _rec: &HitRecord) -> Option<(Point, Ray)> {
+        None
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Point {
+        if !rec.front_face {
+            return Point::default();
+        }
+        match r_in.unit_direction() {
+            Some(unit_direction) => {
+                self.emit * self.falloff((-unit_direction).dot(&self.direction))
+            }
+            None => Point::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+fn record_at_origin() -> HitRecord {
+    HitRecord {
+        p: Point::default(),
+        normal: Point::new(0.0, 1.0, 0.0),
+        t: 1.0,
+        u: 0.0,
+        v: 0.0,
+        front_face: true,
+        tangent: None,
+        bitangent: None,
+        id: None,
+        mat: Arc::new(DiffuseLight::new(Point::default(), 0.0)),
+    }
+}
+
+#[test]
+fn intensity_scales_emission_linearly() {
+    let color = Point::new(1.0, 0.5, 0.25);
+    let rec = record_at_origin();
+    let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Point::new(0.0, -1.0, 0.0));
+    let base = DiffuseLight::new(color, 1.0).emitted(&ray, &rec);
+    assert_eq!(base, color);
+    assert_eq!(
+        DiffuseLight::new(color, 4.0).emitted(&ray, &rec),
+        base * 4.0
+    );
+    assert_eq!(
+        DiffuseLight::new(color, 15.0).emitted(&ray, &rec),
+        Point::new(15.0, 7.5, 3.75)
+    );
+}
+
+#[test]
+fn lights_only_emit_from_their_front_face() {
+    let mut rec = record_at_origin();
+    rec.front_face = false;
+    let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Point::new(0.0, -1.0, 0.0));
+    let color = Point::new(1.0, 1.0, 1.0);
+    assert_eq!(
+        DiffuseLight::new(color, 1.0).emitted(&ray, &rec),
+        Point::default()
+    );
+    let spot = SpotLight::new(color, 1.0, Point::new(0.0, 1.0, 0.0), 10.0, 30.0);
+    assert_eq!(spot.emitted(&ray, &rec), Point::default());
+}
+
+#[test]
+fn spotlight_fades_between_inner_and_outer_cone() {
+    let light = SpotLight::new(
+        Point::new(1.0, 1.0, 1.0),
+        1.0,
+        Point::new(0.0, 1.0, 0.0),
+        10.0,
+        30.0,
+    );
+    let rec = record_at_origin();
+    let towards = |degrees: f64| {
+        let (sin, cos) = Deg::new(degrees).sin_cos();
+        let eye = Point::new(sin, cos, 0.0);
+        light.emitted(&Ray::new(eye, -eye), &rec).x()
+    };
+    assert_eq!(towards(0.0), 1.0);
+    assert_eq!(towards(5.0), 1.0);
+    assert!(towards(20.0) > 0.0 && towards(20.0) < 1.0);
+    assert_eq!(towards(45.0), 0.0);
+}
+
+#[test]
+fn textured_lights_emit_their_texture_at_the_hit() {
+    use crate::texture::CheckerTexture;
+
+    let checker =
+        CheckerTexture::from_colors(Point::new(1.0, 1.0, 1.0), Point::new(1.0, 0.0, 0.0), 4.0);
+    let light = DiffuseLight::with_texture(Arc::new(checker), 2.0);
+    let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Point::new(0.0, -1.0, 0.0));
+
+    let mut rec = record_at_origin();
+    (rec.u, rec.v) = (0.1, 0.1);
+    let even = light.emitted(&ray, &rec);
+    (rec.u, rec.v) = (0.3, 0.1);
+    let odd = light.emitted(&ray, &rec);
+    assert_eq!(even, Point::new(2.0, 2.0, 2.0));
+    assert_eq!(odd, Point::new(2.0, 0.0, 0.0));
+}
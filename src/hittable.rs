@@ -1,17 +1,68 @@
-use std::{cell::RefCell, rc::Rc};
+use std::sync::Arc;
 
 use crate::{
+    aabb::Aabb,
     material::Material,
+    onb::Onb,
     point::{Point, Vector},
     ray::Ray,
+    scene::Json,
+    stats::record_intersection_test,
+    utils::random_between,
 };
 
 pub struct HitRecord {
     pub p: Point,
     pub normal: Vector,
     pub t: f64,
+    /// Surface coordinates of `p`, for texture lookups.
+    pub u: f64,
+    pub v: f64,
     pub front_face: bool,
-    pub mat: Rc<RefCell<dyn Material>>,
+    /// Unit directions along the surface in which `u` and `v` grow, for
+    /// materials that need a frame on it, like normal maps. `None` if the
+    /// surface doesn't have them.
+    pub tangent: Option<Vector>,
+    pub bitangent: Option<Vector>,
+    /// The id of the innermost `IdTag` around what was hit, if any.
+    pub id: Option<u32>,
+    pub mat: Arc<dyn Material>,
+}
+
+impl HitRecord {
+    /// An empty record to be filled in by `hit_into`.
+    pub fn new(mat: Arc<dyn Material>) -> Self {
+        Self {
+            p: Point::default(),
+            normal: Vector::default(),
+            t: f64::MAX,
+            u: 0.0,
+            v: 0.0,
+            front_face: false,
+            tangent: None,
+            bitangent: None,
+            id: None,
+            mat,
+        }
+    }
+
+    /// A copy of this record shaded with `normal` instead.
+    pub fn with_normal(&self, normal: Vector) -> Self {
+        Self {
+            normal,
+            mat: self.mat.clone(),
+            ..*self
+        }
+    }
+
+    /// A basis around `normal` with `u` along `tangent`, or some tangent if
+    /// there is none.
+    pub fn shading_frame(&self) -> Onb {
+        match self.tangent {
+            Some(tangent) => Onb::from_w_and_tangent(&self.normal, &tangent),
+            None => Onb::from_w(&self.normal),
+        }
+    }
 }
 
 pub enum Interval {
@@ -56,38 +107,620 @@ impl Interval {
     }
 }
 
-pub trait Hittable {
+pub trait Hittable: Send + Sync {
     fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord>;
+
+    /// Distance along `r` to the closest hit, without building a record.
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.hit(r, ray_t).map(|rec| rec.t)
+    }
+
+    /// Whether anything is hit within `ray_t`, for shadow and occlusion
+    /// rays that need neither the closest hit nor a record. Lists stop at
+    /// the first hit they find.
+    fn hit_any(&self, r: &Ray, ray_t: &Interval) -> bool {
+        self.hit_distance(r, ray_t).is_some()
+    }
+
+    /// Like `hit`, but writes into `out` and returns whether it was hit,
+    /// leaving `out` untouched on a miss. Implementations can skip cloning
+    /// the material when `out` already holds it.
+    fn hit_into(&self, r: &Ray, ray_t: &Interval, out: &mut HitRecord) -> bool {
+        match self.hit(r, ray_t) {
+            Some(rec) => {
+                *out = rec;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// A sphere, as `(center, radius)`, enclosing everything this object can
+    /// be hit on, if known.
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        None
+    }
+
+    /// An axis-aligned box enclosing everything this object can be hit on,
+    /// if known. Defaults to the box around `bounding_sphere`.
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.bounding_sphere()
+            .map(|(center, radius)| Aabb::around_sphere(center, radius))
+    }
+
+    /// How many bounding boxes, this object's and those nested inside it,
+    /// `r` passes through within `ray_t`, for visualizing how much work a
+    /// ray costs.
+    fn boxes_pierced(&self, r: &Ray, ray_t: &Interval) -> u32 {
+        u32::from(self.bounding_box().is_some_and(|b| b.hit(r, ray_t)))
+    }
+
+    /// Density, over solid angle, of `random` picking `direction` from
+    /// `origin`, for objects that can be sampled as lights. Anything that can
+    /// be passed as a light must override both this and `random`: the
+    /// defaults, no density and a fixed direction, quietly bias every bounce
+    /// that samples it.
+    fn pdf_value(&self, _origin: &Point, _direction: &Vector) -> f64 {
+        0.0
+    }
+
+    /// A random direction from `origin` toward this object, drawn with the
+    /// density `pdf_value` gives.
+    fn random(&self, _origin: &Point) -> Vector {
+        Vector::new(1.0, 0.0, 0.0)
+    }
+
+    /// This object as it's written to a scene file, or `None` if it can't
+    /// be; see `scene::to_json`.
+    fn to_json(&self) -> Option<Json> {
+        None
+    }
 }
 
 pub struct HittableList {
     list: Vec<Box<dyn Hittable>>,
+    order: Option<AxisOrder>,
+}
+
+/// Object indices sorted along one axis by the near and far ends of their
+/// bounding spheres, used to sweep a ray front-to-back through the list.
+struct AxisOrder {
+    /// The box around every object, checked before any of them.
+    bounds: Option<Aabb>,
+    axis: usize,
+    by_min: Vec<(f64, usize)>,
+    by_max: Vec<(f64, usize)>,
 }
 
 impl HittableList {
     pub fn new(list: Option<Vec<Box<dyn Hittable>>>) -> Self {
         match list {
-            None => Self { list: Vec::new() },
-            Some(objs) => Self { list: objs },
+            None => Self {
+                list: Vec::new(),
+                order: None,
+            },
+            Some(objs) => Self {
+                list: objs,
+                order: None,
+            },
         }
     }
 
-    pub fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
-        self.list.iter().fold(None, |hit_record, x| {
-            match x.hit(
+    /// Sorts the objects once along the axis where their bounding spheres are
+    /// most spread out, so `hit` can visit them front-to-back and stop as soon
+    /// as the remaining objects all start beyond the closest hit so far.
+    ///
+    /// Sorting per ray would order objects exactly along each ray's direction,
+    /// but costs `O(n log n)` per ray, which outweighs the tests it saves on
+    /// scenes like the random spheres. A single axis only helps rays with a
+    /// sizeable component along it; rays perpendicular to it test every
+    /// object as before. Also keeps the box around all the objects, so rays
+    /// that miss it are turned away after a single test. Does nothing if any
+    /// object has no bounding sphere, and is undone by `add`.
+    pub fn sort_along_dominant_axis(&mut self) {
+        let Some(spheres) = self
+            .list
+            .iter()
+            .map(|x| x.bounding_sphere())
+            .collect::<Option<Vec<_>>>()
+        else {
+            self.order = None;
+            return;
+        };
+
+        let spread = |axis: usize| {
+            let (min, max) = spheres
+                .iter()
+                .map(|(center, _)| center.axis(axis))
+                .fold((f64::MAX, f64::MIN), |(min, max), x| {
+                    (min.min(x), max.max(x))
+                });
+            max - min
+        };
+        let axis = (0..3)
+            .max_by(|a, b| spread(*a).total_cmp(&spread(*b)))
+            .unwrap_or_default();
+
+        let mut by_min: Vec<_> = spheres
+            .iter()
+            .enumerate()
+            .map(|(i, (center, radius))| (center.axis(axis) - radius, i))
+            .collect();
+        by_min.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut by_max: Vec<_> = spheres
+            .iter()
+            .enumerate()
+            .map(|(i, (center, radius))| (center.axis(axis) + radius, i))
+            .collect();
+        by_max.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        self.order = Some(AxisOrder {
+            bounds: self.bounding_box(),
+            axis,
+            by_min,
+            by_max,
+        });
+    }
+
+    /// Whether `r` misses the box kept by `sort_along_dominant_axis`, so
+    /// none of the objects need testing.
+    fn misses_bounds(&self, r: &Ray, ray_t: &Interval) -> bool {
+        match self.order.as_ref().and_then(|x| x.bounds) {
+            Some(bounds) => {
+                record_intersection_test();
+                !bounds.hit(r, ray_t)
+            }
+            None => false,
+        }
+    }
+
+    /// Distance to and index of the closest object hit.
+    fn closest(&self, r: &Ray, ray_t: &Interval) -> Option<(f64, usize)> {
+        if self.misses_bounds(r, ray_t) {
+            return None;
+        }
+        match &self.order {
+            Some(order) if r.direction().axis(order.axis) != 0.0 => self.sweep(order, r, ray_t),
+            _ => self.hit_all(r, ray_t),
+        }
+    }
+
+    fn sweep(&self, order: &AxisOrder, r: &Ray, ray_t: &Interval) -> Option<(f64, usize)> {
+        let origin = r.origin().axis(order.axis);
+        let direction = r.direction().axis(order.axis);
+        let forward = direction > 0.0;
+        let sorted = if forward {
+            &order.by_min
+        } else {
+            &order.by_max
+        };
+
+        let mut closest: Option<(f64, usize)> = None;
+        for (bound, i) in sorted {
+            let max = closest.map_or(ray_t.max(), |x| x.0);
+            let reach = origin + direction * max;
+            if (forward && reach < *bound) || (!forward && reach > *bound) {
+                break;
+            }
+            let interval = Interval::new_set_interval(ray_t.min(), max);
+            record_intersection_test();
+            if let Some(t) = self.list[*i].hit_distance(r, &interval) {
+                closest = Some((t, *i));
+            }
+        }
+        closest
+    }
+
+    fn hit_all(&self, r: &Ray, ray_t: &Interval) -> Option<(f64, usize)> {
+        self.list.iter().enumerate().fold(None, |closest, (i, x)| {
+            record_intersection_test();
+            match x.hit_distance(
                 r,
-                &Interval::new_set_interval(
-                    ray_t.min(),
-                    hit_record.as_ref().map_or(ray_t.max(), |x: &HitRecord| x.t),
-                ),
+                &Interval::new_set_interval(ray_t.min(), closest.map_or(ray_t.max(), |x| x.0)),
             ) {
-                Some(hr) => Some(hr),
-                None => hit_record,
+                Some(t) => Some((t, i)),
+                None => closest,
             }
         })
     }
 
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// The box around every object, to frame the scene with, or `None` if
+    /// it is empty or holds something unbounded.
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.bounding_box()
+    }
+
+    /// Prints `bounds` to stdout.
+    pub fn print_bounds(&self) {
+        match self.bounds() {
+            Some(bounds) => println!("Scene bounds: {}", bounds),
+            None => println!("Scene bounds: unbounded"),
+        }
+    }
+
     pub fn add(&mut self, obj: Box<dyn Hittable>) {
         self.list.push(obj);
+        self.order = None;
+    }
+
+    /// Moves every object of `other` into this list, undoing
+    /// `sort_along_dominant_axis` like `add`.
+    pub fn append(&mut self, mut other: HittableList) {
+        self.list.append(&mut other.list);
+        self.order = None;
+    }
+}
+
+impl Hittable for HittableList {
+    /// Finds the closest object by distance alone and only builds a record,
+    /// cloning its material, for that one.
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let (_, i) = self.closest(r, ray_t)?;
+        self.list[i].hit(r, ray_t)
+    }
+
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.closest(r, ray_t).map(|(t, _)| t)
+    }
+
+    fn hit_any(&self, r: &Ray, ray_t: &Interval) -> bool {
+        if self.misses_bounds(r, ray_t) {
+            return false;
+        }
+        self.list.iter().any(|x| {
+            record_intersection_test();
+            x.hit_any(r, ray_t)
+        })
     }
+
+    /// Finds the closest object like `hit` and writes only its record into
+    /// `out`, so a record reused across calls only has its material replaced
+    /// when a different one is hit.
+    fn hit_into(&self, r: &Ray, ray_t: &Interval, out: &mut HitRecord) -> bool {
+        match self.closest(r, ray_t) {
+            Some((_, i)) => self.list[i].hit_into(r, ray_t, out),
+            None => false,
+        }
+    }
+
+    /// The average of the objects' densities, as `random` picks one of them
+    /// at random to aim for.
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        if self.list.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self
+            .list
+            .iter()
+            .map(|x| x.pdf_value(origin, direction))
+            .sum();
+        sum / self.list.len() as f64
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        if self.list.is_empty() {
+            return Vector::new(1.0, 0.0, 0.0);
+        }
+        let i = (random_between(0.0, self.list.len() as f64) as usize).min(self.list.len() - 1);
+        self.list[i].random(origin)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let boxes = self
+            .list
+            .iter()
+            .map(|x| x.bounding_box())
+            .collect::<Option<Vec<_>>>()?;
+        let (first, rest) = boxes.split_first()?;
+        Some(rest.iter().fold(*first, |acc, x| acc.union(x)))
+    }
+
+    fn boxes_pierced(&self, r: &Ray, ray_t: &Interval) -> u32 {
+        let own = u32::from(self.bounding_box().is_some_and(|b| b.hit(r, ray_t)));
+        own + self
+            .list
+            .iter()
+            .map(|x| x.boxes_pierced(r, ray_t))
+            .sum::<u32>()
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        let spheres = self
+            .list
+            .iter()
+            .map(|x| x.bounding_sphere())
+            .collect::<Option<Vec<_>>>()?;
+        let (first, rest) = spheres.split_first()?;
+
+        let extent = |(center, radius): &(Point, f64)| {
+            let r = Vector::new(*radius, *radius, *radius);
+            (*center - r, *center + r)
+        };
+        let (min, max) = rest
+            .iter()
+            .map(extent)
+            .fold(extent(first), |acc, x| (acc.0.min(&x.0), acc.1.max(&x.1)));
+
+        let center = 0.5 * (min + max);
+        let radius = spheres
+            .iter()
+            .map(|(c, r)| (*c - center).len() + r)
+            .fold(0.0, f64::max);
+        Some((center, radius))
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        let objects = self
+            .list
+            .iter()
+            .map(|x| x.to_json())
+            .collect::<Option<_>>()?;
+        Some(Json::tagged(
+            "list",
+            [
+                ("objects", Json::Array(objects)),
+                ("sorted", self.order.is_some().into()),
+            ],
+        ))
+    }
+}
+
+impl Extend<Box<dyn Hittable>> for HittableList {
+    fn extend<T: IntoIterator<Item = Box<dyn Hittable>>>(&mut self, iter: T) {
+        self.list.extend(iter);
+        self.order = None;
+    }
+}
+
+impl FromIterator<Box<dyn Hittable>> for HittableList {
+    fn from_iter<T: IntoIterator<Item = Box<dyn Hittable>>>(iter: T) -> Self {
+        Self::new(Some(iter.into_iter().collect()))
+    }
+}
+
+#[test]
+fn bounding_sphere_encloses_two_spheres() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let mut list = HittableList::new(None);
+    assert!(list.bounding_sphere().is_none());
+
+    list.add(Box::new(Sphere::new(
+        Point::new(-1.0, 0.0, 0.0),
+        1.0,
+        mat.clone(),
+    )));
+    list.add(Box::new(Sphere::new(Point::new(2.0, 0.0, 0.0), 1.0, mat)));
+    let (center, radius) = list.bounding_sphere().unwrap();
+    assert_eq!(center, Point::new(0.5, 0.0, 0.0));
+    assert_eq!(radius, 2.5);
+}
+
+#[test]
+fn sorted_list_finds_the_same_hits() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let mut list = HittableList::new(None);
+    for i in -5..5 {
+        for j in -5..5 {
+            let center = Point::new(f64::from(i), 0.0, f64::from(j));
+            list.add(Box::new(Sphere::new(center, 0.3, mat.clone())));
+        }
+    }
+    let rays: Vec<_> = (0..200)
+        .map(|_| {
+            let origin = Point::random_between(-6.0, 6.0);
+            Ray::new(origin, Point::random_between(-1.0, 1.0))
+        })
+        .collect();
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let unsorted: Vec<_> = rays
+        .iter()
+        .map(|r| list.hit(r, &interval).map(|x| x.t))
+        .collect();
+
+    list.sort_along_dominant_axis();
+    assert!(list.order.is_some());
+    let sorted: Vec<_> = rays
+        .iter()
+        .map(|r| list.hit(r, &interval).map(|x| x.t))
+        .collect();
+    assert_eq!(unsorted, sorted);
+
+    // `hit_into` sweeps too, testing no more objects than `hit` does.
+    let mut out = HitRecord::new(mat);
+    for (r, t) in rays.iter().zip(&sorted) {
+        let before = crate::stats::intersection_tests();
+        list.hit(r, &interval);
+        let hit_tests = crate::stats::intersection_tests() - before;
+        let hit = list.hit_into(r, &interval, &mut out);
+        let into_tests = crate::stats::intersection_tests() - before - hit_tests;
+        assert_eq!(hit.then_some(out.t), *t);
+        assert_eq!(into_tests, hit_tests);
+    }
+}
+
+#[test]
+fn hit_into_reuses_the_record_material() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let near: Arc<dyn Material> = Arc::new(Lambertian::new(Point::default()));
+    let far: Arc<dyn Material> = Arc::new(Lambertian::new(Point::default()));
+    let mut list = HittableList::new(None);
+    list.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -4.0),
+        1.0,
+        far.clone(),
+    )));
+    list.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -2.0),
+        0.5,
+        near.clone(),
+    )));
+
+    let r = Ray::new(Point::default(), Vector::new(0.0, 0.0, -1.0));
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let mut out = HitRecord::new(near.clone());
+    let (near_count, far_count) = (Arc::strong_count(&near), Arc::strong_count(&far));
+
+    for _ in 0..10 {
+        assert!(list.hit_into(&r, &interval, &mut out));
+        assert_eq!(out.t, 1.5);
+        assert!(Arc::ptr_eq(&out.mat, &near));
+    }
+    assert_eq!(Arc::strong_count(&near), near_count);
+    assert_eq!(Arc::strong_count(&far), far_count);
+
+    let miss = Ray::new(Point::default(), Vector::new(0.0, 1.0, 0.0));
+    assert!(!list.hit_into(&miss, &interval, &mut out));
+    assert_eq!(out.t, 1.5);
+}
+
+#[test]
+fn hit_clones_only_the_closest_material() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mats: Vec<Arc<dyn Material>> = (0..4)
+        .map(|_| Arc::new(Lambertian::new(Point::default())) as _)
+        .collect();
+    let mut list = HittableList::new(None);
+    for (i, mat) in mats.iter().enumerate().rev() {
+        let center = Point::new(0.0, 0.0, -2.0 * (i as f64 + 1.0));
+        list.add(Box::new(Sphere::new(center, 0.5, mat.clone())));
+    }
+    let before: Vec<_> = mats.iter().map(Arc::strong_count).collect();
+
+    let r = Ray::new(Point::default(), Vector::new(0.0, 0.0, -1.0));
+    let rec = list
+        .hit(&r, &Interval::new_set_interval(0.001, f64::MAX))
+        .unwrap();
+    assert_eq!(rec.t, 1.5);
+    assert!(Arc::ptr_eq(&rec.mat, &mats[0]));
+
+    let deltas: Vec<_> = mats
+        .iter()
+        .zip(before)
+        .map(|(x, before)| Arc::strong_count(x) - before)
+        .collect();
+    assert_eq!(deltas, [1, 0, 0, 0]);
+}
+
+#[test]
+fn collects_from_an_iterator() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat: Arc<dyn Material> = Arc::new(Lambertian::new(Point::default()));
+    let mut list: HittableList = (0..3)
+        .map(|i| {
+            let center = Point::new(f64::from(i), 0.0, 0.0);
+            Box::new(Sphere::new(center, 0.5, mat.clone())) as Box<dyn Hittable>
+        })
+        .collect();
+    assert_eq!(list.len(), 3);
+
+    list.sort_along_dominant_axis();
+    list.extend(HittableList::new(None).list);
+    list.extend([Box::new(Sphere::new(Point::default(), 1.0, mat)) as Box<dyn Hittable>]);
+    assert_eq!(list.len(), 4);
+    assert!(list.order.is_none());
+}
+
+#[test]
+fn nested_lists_are_hit_like_flat_ones() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat: Arc<dyn Material> = Arc::new(Lambertian::new(Point::default()));
+    let sphere = |z| Box::new(Sphere::new(Point::new(0.0, 0.0, z), 0.5, mat.clone()));
+    let mut inner = HittableList::new(None);
+    inner.add(sphere(-4.0));
+    inner.add(sphere(-2.0));
+    let mut outer = HittableList::new(None);
+    outer.add(sphere(-6.0));
+    outer.add(Box::new(inner));
+
+    let r = Ray::new(Point::default(), Vector::new(0.0, 0.0, -1.0));
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    assert_eq!(outer.hit(&r, &interval).map(|x| x.t), Some(1.5));
+    assert_eq!(outer.hit_distance(&r, &interval), Some(1.5));
+    let (center, radius) = outer.bounding_sphere().unwrap();
+    assert_eq!((center, radius), (Point::new(0.0, 0.0, -4.0), 2.5));
+}
+
+#[test]
+fn hit_any_agrees_with_hit() {
+    use crate::{matrix::Mat4, scenes, transform::Transform};
+
+    let mut world = scenes::random_spheres(5);
+    let moved = Transform::new(
+        Box::new(scenes::random_spheres(6)),
+        Mat4::translation(Vector::new(0.0, 3.0, 0.0)),
+    );
+    world.add(Box::new(moved.unwrap()));
+
+    let mut hits = 0;
+    for i in 0..500 {
+        let origin = Point::new(0.0, 2.0, 0.0) + 6.0 * Point::random_in_unit_disk();
+        let r = Ray::new(origin, Point::random_in_unit_vector().unwrap());
+        // Short intervals like shadow rays as well as unbounded ones.
+        let max = if i % 2 == 0 { 1.0 } else { f64::MAX };
+        let interval = Interval::new_set_interval(0.001, max);
+        let any = world.hit_any(&r, &interval);
+        assert_eq!(any, world.hit(&r, &interval).is_some());
+        hits += usize::from(any);
+    }
+    assert!(hits > 0 && hits < 500, "{} hits", hits);
+}
+
+#[test]
+fn bounds_are_the_union_of_the_objects_boxes() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)));
+    let world: HittableList = [
+        Sphere::new(Point::new(0.0, 0.0, -1.0), 0.5, mat.clone()),
+        Sphere::new(Point::new(0.0, -100.5, -1.0), 100.0, mat),
+    ]
+    .into_iter()
+    .map(|x| Box::new(x) as Box<dyn Hittable>)
+    .collect();
+
+    let bounds = world.bounds().unwrap();
+    assert_eq!(bounds.min(), Point::new(-100.0, -200.5, -101.0));
+    assert_eq!(bounds.max(), Point::new(100.0, 0.5, 99.0));
+    assert_eq!(bounds.center(), Point::new(0.0, -100.0, -1.0));
+    assert_eq!(
+        bounds.to_string(),
+        "from -100 -200.5 -101 to 100 0.5 99, center 0 -100 -1, size 200 201 200"
+    );
+    assert!(HittableList::new(None).bounds().is_none());
+}
+
+#[test]
+fn append_moves_the_objects_and_grows_the_bounds() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat: Arc<dyn Material> = Arc::new(Lambertian::new(Point::default()));
+    let sphere = |x| Box::new(Sphere::new(Point::new(x, 0.0, 0.0), 0.5, mat.clone()));
+    let mut list = HittableList::new(Some(vec![sphere(0.0), sphere(1.0)]));
+    list.sort_along_dominant_axis();
+    let mut other = HittableList::new(None);
+    other.add(sphere(4.0));
+
+    list.append(other);
+    assert_eq!(list.len(), 3);
+    assert!(list.order.is_none());
+    let bounds = list.bounds().unwrap();
+    assert_eq!(bounds.min(), Point::new(-0.5, -0.5, -0.5));
+    assert_eq!(bounds.max(), Point::new(4.5, 0.5, 0.5));
 }
@@ -0,0 +1,104 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable, Interval},
+    point::{Point, Vector},
+    ray::Ray,
+    scene::Json,
+};
+
+/// Tags every hit on a child `Hittable` with `id`, for telling objects apart
+/// when compositing, as in `Shading::ObjectId`. A tag nested inside another
+/// wins over it.
+pub struct IdTag {
+    id: u32,
+    child: Box<dyn Hittable>,
+}
+
+impl IdTag {
+    pub fn new(id: u32, child: Box<dyn Hittable>) -> Self {
+        Self { id, child }
+    }
+}
+
+impl Hittable for IdTag {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let mut record = self.child.hit(r, ray_t)?;
+        record.id.get_or_insert(self.id);
+        Some(record)
+    }
+
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.child.hit_distance(r, ray_t)
+    }
+
+    fn hit_any(&self, r: &Ray, ray_t: &Interval) -> bool {
+        self.child.hit_any(r, ray_t)
+    }
+
+    fn hit_into(&self, r: &Ray, ray_t: &Interval, out: &mut HitRecord) -> bool {
+        // Whatever `out` held before may have been tagged by something else.
+        let previous = out.id.take();
+        if self.child.hit_into(r, ray_t, out) {
+            out.id.get_or_insert(self.id);
+            true
+        } else {
+            out.id = previous;
+            false
+        }
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        self.child.bounding_sphere()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.child.bounding_box()
+    }
+
+    fn boxes_pierced(&self, r: &Ray, ray_t: &Interval) -> u32 {
+        self.child.boxes_pierced(r, ray_t)
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        self.child.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        self.child.random(origin)
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "id_tag",
+            [
+                ("id", f64::from(self.id).into()),
+                ("object", self.child.to_json()?),
+            ],
+        ))
+    }
+}
+
+#[test]
+fn hits_on_a_tagged_object_report_its_id() {
+    use std::sync::Arc;
+
+    use crate::{hittable::HittableList, lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)));
+    let sphere = |z| Box::new(Sphere::new(Point::new(0.0, 0.0, z), 0.5, mat.clone()));
+    let tagged = IdTag::new(7, Box::new(IdTag::new(3, sphere(-2.0))));
+    let world = HittableList::new(Some(vec![sphere(-4.0), Box::new(tagged)]));
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+
+    let toward = Ray::new(Point::default(), Vector::new(0.0, 0.0, -1.0));
+    assert_eq!(world.hit(&toward, &interval).unwrap().id, Some(3));
+    let mut out = HitRecord::new(mat.clone());
+    assert!(world.hit_into(&toward, &interval, &mut out));
+    assert_eq!(out.id, Some(3));
+
+    // From behind, the untagged sphere is closer.
+    let back = Ray::new(Point::new(0.0, 0.0, -6.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(world.hit(&back, &interval).unwrap().id, None);
+    assert!(world.hit_into(&back, &interval, &mut out));
+    assert_eq!(out.id, None);
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind},
+    point::Point,
+    ray::Ray,
+    scene::Json,
+    texture::{SolidColor, Texture},
+};
+
+/// Scatters uniformly in every direction, regardless of the incoming ray or
+/// the surface normal, as the particles in a participating medium do.
+pub struct Isotropic {
+    albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(color: Point) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::Isotropic
+    }
+
+    fn scatter(&self, _r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
+        Some((
+            self.albedo.value(rec.u, rec.v, &rec.p),
+            Ray::new(rec.p, Point::random_in_unit_vector()?),
+        ))
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "isotropic",
+            [("albedo", self.albedo.to_json()?)],
+        ))
+    }
+}
+
+#[test]
+fn scatters_evenly_over_the_sphere() {
+    let mat = Arc::new(Isotropic::new(Point::new(0.5, 0.5, 0.5)));
+    let mut rec = HitRecord::new(mat.clone());
+    rec.normal = Point::new(0.0, 1.0, 0.0);
+    let r_in = Ray::new(Point::new(0.0, 1.0, 0.0), Point::new(0.0, -1.0, 0.0));
+
+    let samples = 20_000;
+    let directions: Vec<_> = (0..samples)
+        .map(|_| mat.scatter(&r_in, &rec).unwrap().1.direction())
+        .collect();
+    let mean = |f: &dyn Fn(&Point) -> f64| directions.iter().map(f).sum::<f64>() / samples as f64;
+
+    for axis in 0..3 {
+        // A uniform direction has each component averaging 0 with a
+        // second moment of 1/3.
+        assert!(mean(&|d| d.axis(axis)).abs() < 0.02);
+        assert!((mean(&|d| d.axis(axis).powi(2)) - 1.0 / 3.0).abs() < 0.02);
+    }
+}
@@ -1,23 +1,108 @@
-use crate::{hittable::HitRecord, material::Material, point::Point, ray::Ray};
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind, ScatterRecord, Scattered},
+    pdf::{CosinePdf, Pdf},
+    point::Point,
+    ray::Ray,
+    scene::Json,
+    texture::{SolidColor, Texture},
+};
 
 pub struct Lambertian {
-    color: Point,
+    albedo: Arc<dyn Texture>,
+    cosine_sampling: bool,
 }
 
 impl Lambertian {
     pub fn new(color: Point) -> Self {
-        Self { color }
+        Self::with_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self {
+            albedo,
+            cosine_sampling: false,
+        }
+    }
+
+    /// Makes `scatter` sample directions exactly cosine-weighted about the
+    /// normal, instead of as the normal plus a random unit vector. The
+    /// camera draws from the cosine distribution through `scatter_record`
+    /// either way.
+    pub fn with_cosine_sampling(mut self) -> Self {
+        self.cosine_sampling = true;
+        self
     }
 }
 
 impl Material for Lambertian {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::Lambertian
+    }
+
+    fn scatter_record(&self, _r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        Some(ScatterRecord {
+            attenuation: self.albedo.value(rec.u, rec.v, &rec.p),
+            scattered: Scattered::Pdf(Box::new(CosinePdf::new(&rec.normal))),
+        })
+    }
+
+    fn scattering_pdf(&self, _r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        CosinePdf::new(&rec.normal).value(&scattered.direction())
+    }
+
     fn scatter(&self, _r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
-        let mut scatter_direction = rec.normal + Point::random_in_unit_vector()?;
+        let scatter_direction = if self.cosine_sampling {
+            CosinePdf::new(&rec.normal).generate()
+        } else {
+            let direction = rec.normal + Point::random_in_unit_vector()?;
+            if direction.near_zero() {
+                rec.normal
+            } else {
+                direction
+            }
+        };
 
-        if scatter_direction.near_zero() {
-            scatter_direction = rec.normal;
-        }
+        Some((
+            self.albedo.value(rec.u, rec.v, &rec.p),
+            Ray::new(rec.p, scatter_direction),
+        ))
+    }
 
-        Some((self.color, Ray::new(rec.p, scatter_direction)))
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "lambertian",
+            [
+                ("albedo", self.albedo.to_json()?),
+                ("cosine_sampling", self.cosine_sampling.into()),
+            ],
+        ))
     }
 }
+
+#[test]
+fn cosine_sampling_follows_the_cosine_distribution() {
+    let mat = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)).with_cosine_sampling());
+    let mut rec = HitRecord::new(mat.clone());
+    rec.normal = Point::new(0.0, 0.6, 0.8);
+    let r_in = Ray::new(Point::new(0.0, 1.0, 1.0), Point::new(0.0, -1.0, -1.0));
+
+    let samples = 20_000;
+    let cosines: Vec<_> = (0..samples)
+        .map(|_| {
+            let direction = mat.scatter(&r_in, &rec).unwrap().1.direction();
+            assert!((direction.len() - 1.0).abs() < 1e-9);
+            direction.dot(&rec.normal)
+        })
+        .collect();
+
+    // Cosines drawn with density 2c on [0, 1] have mean 2/3 and second
+    // moment 1/2.
+    let mean = cosines.iter().sum::<f64>() / samples as f64;
+    let second = cosines.iter().map(|c| c * c).sum::<f64>() / samples as f64;
+    assert!(cosines.iter().all(|c| *c >= 0.0));
+    assert!((mean - 2.0 / 3.0).abs() < 0.01, "mean {}", mean);
+    assert!((second - 0.5).abs() < 0.01, "second moment {}", second);
+}
@@ -0,0 +1,40 @@
+pub mod aabb;
+pub mod animated;
+pub mod brushed_metal;
+pub mod bump;
+pub mod camera;
+pub mod cube_map;
+pub mod denoise;
+pub mod dielectric;
+pub mod diffuse_light;
+pub mod hittable;
+pub mod id_tag;
+pub mod isotropic;
+pub mod lambertian;
+pub mod material;
+pub mod matrix;
+pub mod metal;
+pub mod mix;
+pub mod normal_map;
+pub mod obj;
+pub mod onb;
+pub mod pdf;
+pub mod phong;
+pub mod point;
+pub mod quad;
+pub mod ray;
+pub mod scene;
+pub mod scenes;
+pub mod shadow_catcher;
+#[cfg(feature = "simd")]
+mod simd;
+pub mod spectrum;
+pub mod sphere;
+pub mod stats;
+pub mod texture;
+pub mod transform;
+pub mod triangle;
+pub mod two_sided;
+pub mod unlit;
+pub mod utils;
+pub mod visibility;
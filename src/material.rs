@@ -1,5 +1,192 @@
-use crate::{hittable::HitRecord, point::Point, ray::Ray};
+use crate::{hittable::HitRecord, pdf::Pdf, point::Point, ray::Ray, scene::Json};
+
+/// Which material a `dyn Material` is, for tools that inspect scenes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MaterialKind {
+    Lambertian,
+    Metal,
+    BrushedMetal,
+    Dielectric,
+    Phong,
+    Isotropic,
+    DiffuseLight,
+    SpotLight,
+    Mix,
+    NormalMapped,
+    Bump,
+    Animated,
+    Unlit,
+    ShadowCatcher,
+    TwoSided,
+}
+
+/// How a material scatters a ray, for the camera to trace.
+pub struct ScatterRecord {
+    pub attenuation: Point,
+    pub scattered: Scattered,
+}
+
+pub enum Scattered {
+    /// A ray chosen by the material, weighted by `attenuation` alone and
+    /// never mixed with light sampling. Specular materials scatter this way.
+    Ray(Ray),
+    /// A distribution to draw the scattered direction from, which the camera
+    /// may mix with sampling toward lights. A direction drawn from the mix
+    /// is weighted by `attenuation * scattering_pdf / mix.value`.
+    Pdf(Box<dyn Pdf>),
+}
+
+pub trait Material: Send + Sync {
+    fn kind(&self) -> MaterialKind;
+
+    /// Whether this scatters like a perfect mirror or glass, in a single
+    /// direction set by the incoming ray.
+    fn is_specular(&self) -> bool {
+        false
+    }
+
+    /// Whether what this emits lights other surfaces. If not, it is only
+    /// seen directly, by camera rays and through mirrors and glass.
+    fn lights_scene(&self) -> bool {
+        true
+    }
 
-pub trait Material {
     fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)>;
+
+    /// Like `scatter`, but materials that can be importance sampled return
+    /// the distribution of their scattered directions instead of one ray.
+    fn scatter_record(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let (attenuation, ray) = self.scatter(r_in, rec)?;
+        Some(ScatterRecord {
+            attenuation,
+            scattered: Scattered::Ray(ray),
+        })
+    }
+
+    /// Density, over solid angle, of this material scattering `r_in` into
+    /// `scattered`, for materials returning `Scattered::Pdf`.
+    fn scattering_pdf(&self, _r_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f64 {
+        0.0
+    }
+
+    /// Light given off at `rec` back along `r_in`, the ray that hit it. The
+    /// ray lets emitters depend on the viewing direction; anything not a
+    /// light keeps the default of black.
+    fn emitted(&self, _r_in: &Ray, _rec: &HitRecord) -> Point {
+        Point::default()
+    }
+
+    /// This material as it's written to a scene file, or `None` if it
+    /// can't be.
+    fn to_json(&self) -> Option<Json> {
+        None
+    }
+}
+
+/// A material that shades `inner` with its normals perturbed, like
+/// `NormalMapped` and `Bump`: every call is passed on to `inner` with the
+/// record `perturbed` gives, bar `emitted`, which keeps the surface's own.
+pub(crate) trait PerturbsNormal: Send + Sync {
+    const KIND: MaterialKind;
+
+    fn inner(&self) -> &dyn Material;
+
+    fn perturbed(&self, rec: &HitRecord) -> HitRecord;
+}
+
+impl<T: PerturbsNormal> Material for T {
+    fn kind(&self) -> MaterialKind {
+        T::KIND
+    }
+
+    fn is_specular(&self) -> bool {
+        self.inner().is_specular()
+    }
+
+    fn lights_scene(&self) -> bool {
+        self.inner().lights_scene()
+    }
+
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
+        self.inner().scatter(r_in, &self.perturbed(rec))
+    }
+
+    fn scatter_record(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        self.inner().scatter_record(r_in, &self.perturbed(rec))
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        self.inner()
+            .scattering_pdf(r_in, &self.perturbed(rec), scattered)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Point {
+        self.inner().emitted(r_in, rec)
+    }
+}
+
+#[test]
+fn materials_report_their_kind() {
+    use std::sync::Arc;
+
+    use crate::{
+        animated::Animated,
+        brushed_metal::BrushedMetal,
+        bump::Bump,
+        dielectric::Dielectric,
+        diffuse_light::{DiffuseLight, SpotLight},
+        isotropic::Isotropic,
+        lambertian::Lambertian,
+        metal::Metal,
+        mix::Mix,
+        normal_map::NormalMapped,
+        phong::Phong,
+        shadow_catcher::ShadowCatcher,
+        texture::SolidColor,
+        two_sided::TwoSided,
+        unlit::Unlit,
+    };
+
+    let c = Point::new(0.5, 0.5, 0.5);
+    let lambertian: Arc<dyn Material> = Arc::new(Lambertian::new(c));
+    let texture = Arc::new(SolidColor::new(c));
+    let animated = Animated::new(move |_| Lambertian::new(c));
+    let materials: [(&dyn Material, MaterialKind); 15] = [
+        (&*lambertian, MaterialKind::Lambertian),
+        (&Metal::new(c, 0.0), MaterialKind::Metal),
+        (
+            &BrushedMetal::new(c, 0.1, 0.2, Point::new(1.0, 0.0, 0.0)),
+            MaterialKind::BrushedMetal,
+        ),
+        (&Dielectric::new(1.5), MaterialKind::Dielectric),
+        (&Phong::new(c, c, 10.0), MaterialKind::Phong),
+        (&Isotropic::new(c), MaterialKind::Isotropic),
+        (&DiffuseLight::new(c, 1.0), MaterialKind::DiffuseLight),
+        (
+            &SpotLight::new(c, 1.0, Point::new(0.0, -1.0, 0.0), 10.0, 20.0),
+            MaterialKind::SpotLight,
+        ),
+        (
+            &Mix::new(lambertian.clone(), lambertian.clone(), 0.5),
+            MaterialKind::Mix,
+        ),
+        (
+            &NormalMapped::new(lambertian.clone(), texture.clone()),
+            MaterialKind::NormalMapped,
+        ),
+        (
+            &Bump::new(lambertian.clone(), texture, 1.0),
+            MaterialKind::Bump,
+        ),
+        (&animated, MaterialKind::Animated),
+        (&Unlit::new(c), MaterialKind::Unlit),
+        (&ShadowCatcher::new(c), MaterialKind::ShadowCatcher),
+        (
+            &TwoSided::new(lambertian.clone(), lambertian.clone()),
+            MaterialKind::TwoSided,
+        ),
+    ];
+    for (material, kind) in materials {
+        assert_eq!(material.kind(), kind);
+    }
 }
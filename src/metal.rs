@@ -1,31 +1,118 @@
+use std::sync::Arc;
+
 use crate::{
     hittable::HitRecord,
-    material::Material,
-    point::{reflect, Point},
+    material::{Material, MaterialKind},
+    point::Point,
     ray::Ray,
+    scene::Json,
+    texture::Texture,
+    utils::schlick_f0,
 };
 
 pub struct Metal {
     color: Point,
     fuzz: f64,
+    roughness: Option<Arc<dyn Texture>>,
 }
 
 impl Metal {
     pub fn new(color: Point, fuzz: f64) -> Self {
-        Self { color, fuzz }
+        Self {
+            color,
+            fuzz,
+            roughness: None,
+        }
+    }
+
+    /// A metal whose fuzz varies over the surface, looked up in
+    /// `roughness`, a grayscale texture whose channels are averaged and
+    /// clamped to `[0, 1]`.
+    pub fn with_roughness(color: Point, roughness: Arc<dyn Texture>) -> Self {
+        Self {
+            color,
+            fuzz: 0.0,
+            roughness: Some(roughness),
+        }
+    }
+
+    fn fuzz_at(&self, rec: &HitRecord) -> f64 {
+        match &self.roughness {
+            Some(roughness) => {
+                let c = roughness.value(rec.u, rec.v, &rec.p);
+                ((c.x() + c.y() + c.z()) / 3.0).clamp(0.0, 1.0)
+            }
+            None => self.fuzz,
+        }
     }
 }
 
 impl Material for Metal {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::Metal
+    }
+
+    fn is_specular(&self) -> bool {
+        self.roughness.is_none() && self.fuzz == 0.0
+    }
+
     fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
-        let reflected = reflect(&r_in.direction().unit()?, &rec.normal);
+        let unit_direction = r_in.unit_direction()?;
+        let reflected = unit_direction.reflect(&rec.normal);
+
+        let cos_theta = (-unit_direction).dot(&rec.normal).clamp(0.0, 1.0);
+        let attenuation = Point::new(
+            schlick_f0(cos_theta, self.color.x()),
+            schlick_f0(cos_theta, self.color.y()),
+            schlick_f0(cos_theta, self.color.z()),
+        );
 
         Some((
-            self.color,
+            attenuation,
             Ray::new(
                 rec.p,
-                reflected + self.fuzz * Point::random_in_unit_vector()?,
+                reflected + self.fuzz_at(rec) * Point::random_in_unit_vector()?,
             ),
         ))
     }
+
+    fn to_json(&self) -> Option<Json> {
+        let mut json = Json::tagged(
+            "metal",
+            [("color", self.color.into()), ("fuzz", self.fuzz.into())],
+        );
+        if let (Json::Object(fields), Some(roughness)) = (&mut json, &self.roughness) {
+            fields.push(("roughness".to_string(), roughness.to_json()?));
+        }
+        Some(json)
+    }
+}
+
+#[test]
+fn roughness_maps_vary_the_fuzz_across_the_surface() {
+    use crate::{lambertian::Lambertian, texture::CheckerTexture};
+
+    let checker = CheckerTexture::from_colors(Point::default(), Point::new(3.0, 3.0, 3.0), 2.0);
+    let metal = Metal::with_roughness(Point::new(0.9, 0.9, 0.9), Arc::new(checker));
+    assert!(!metal.is_specular());
+
+    let normal = Point::new(0.0, 1.0, 0.0);
+    let at = |u: f64| {
+        let mut rec = HitRecord::new(Arc::new(Lambertian::new(Point::default())));
+        (rec.normal, rec.u) = (normal, u);
+        rec
+    };
+    let ray = Ray::new(Point::new(-1.0, 1.0, 0.0), Point::new(1.0, -1.0, 0.0));
+    let mirror = ray.unit_direction().unwrap().reflect(&normal);
+    let deviation = |u: f64| {
+        let (_, scattered) = metal.scatter(&ray, &at(u)).unwrap();
+        (scattered.direction() - mirror).len()
+    };
+
+    // Smooth in the even checks, and as rough as can be in the odd ones,
+    // where the texture's 3 is clamped to 1.
+    assert_eq!(metal.fuzz_at(&at(0.25)), 0.0);
+    assert_eq!(metal.fuzz_at(&at(0.75)), 1.0);
+    assert!(deviation(0.25) < 1e-12);
+    assert!((0..10).any(|_| deviation(0.75) > 0.1));
 }
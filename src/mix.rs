@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind, ScatterRecord},
+    point::Point,
+    ray::Ray,
+    scene::Json,
+};
+
+pub struct Mix {
+    first: Arc<dyn Material>,
+    second: Arc<dyn Material>,
+    weight: f64,
+}
+
+impl Mix {
+    /// Scatters like `second` with probability `weight` and like `first`
+    /// otherwise, so `0.0` is all `first` and `1.0` is all `second`.
+    pub fn new(first: Arc<dyn Material>, second: Arc<dyn Material>, weight: f64) -> Self {
+        Self {
+            first,
+            second,
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+
+    /// The child `r_in` hitting `rec` behaves like. Drawn from a hash of the
+    /// two rather than the generator, so every call made for one bounce sees
+    /// the same child.
+    fn pick(&self, r_in: &Ray, rec: &HitRecord) -> &dyn Material {
+        let (d, p) = (r_in.direction(), rec.p);
+        let hash = [d.x(), d.y(), d.z(), p.x(), p.y(), p.z(), rec.t]
+            .into_iter()
+            .fold(0x9e37_79b9_7f4a_7c15_u64, |hash, x| {
+                // The splitmix64 finalizer, to spread every bit of the input.
+                let mut z = hash ^ x.to_bits();
+                z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                z ^ (z >> 31)
+            });
+        let random = (hash >> 11) as f64 / (1u64 << 53) as f64;
+        if random < self.weight {
+            self.second.as_ref()
+        } else {
+            self.first.as_ref()
+        }
+    }
+}
+
+impl Material for Mix {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::Mix
+    }
+
+    /// Only if both children are, as this can't tell which one a ray sees.
+    fn is_specular(&self) -> bool {
+        self.first.is_specular() && self.second.is_specular()
+    }
+
+    fn lights_scene(&self) -> bool {
+        self.first.lights_scene() || self.second.lights_scene()
+    }
+
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
+        self.pick(r_in, rec).scatter(r_in, rec)
+    }
+
+    fn scatter_record(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        self.pick(r_in, rec).scatter_record(r_in, rec)
+    }
+
+    /// The density of the child `scatter_record` picked: weighing both by
+    /// `weight` here would mix one child's attenuation with the other's
+    /// density.
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        self.pick(r_in, rec).scattering_pdf(r_in, rec, scattered)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Point {
+        self.pick(r_in, rec).emitted(r_in, rec)
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "mix",
+            [
+                ("first", self.first.to_json()?),
+                ("second", self.second.to_json()?),
+                ("weight", self.weight.into()),
+            ],
+        ))
+    }
+}
+
+#[test]
+fn picks_materials_according_to_weight() {
+    use crate::lambertian::Lambertian;
+
+    let first_color = Point::new(1.0, 0.0, 0.0);
+    let first: Arc<dyn Material> = Arc::new(Lambertian::new(first_color));
+    let second = Arc::new(Lambertian::new(Point::new(0.0, 0.0, 1.0)));
+    let mix = Mix::new(first.clone(), second, 0.3);
+
+    let mut rec = HitRecord::new(first);
+    rec.normal = Point::new(0.0, 1.0, 0.0);
+    rec.t = 1.0;
+    rec.front_face = true;
+
+    let samples = 20_000;
+    let second_count = (0..samples)
+        .filter_map(|_| {
+            let toward = Point::random_in_unit_vector()?;
+            let ray = Ray::new(-toward, toward);
+            mix.scatter(&ray, &rec)
+        })
+        .filter(|(attenuation, _)| *attenuation != first_color)
+        .count();
+    let fraction = second_count as f64 / f64::from(samples);
+    assert!((fraction - 0.3).abs() < 0.02, "fraction was {}", fraction);
+}
+
+#[test]
+fn forwards_light_and_sampling_to_the_picked_child() {
+    use crate::{diffuse_light::DiffuseLight, lambertian::Lambertian, material::Scattered};
+
+    let white = Point::new(1.0, 1.0, 1.0);
+    let lambertian: Arc<dyn Material> = Arc::new(Lambertian::new(white));
+    let light: Arc<dyn Material> = Arc::new(DiffuseLight::new(white, 4.0));
+    let mix = Mix::new(lambertian.clone(), light, 0.5);
+    assert!(mix.lights_scene() && !mix.is_specular());
+
+    let mut rec = HitRecord::new(lambertian);
+    rec.normal = Point::new(0.0, 1.0, 0.0);
+    rec.front_face = true;
+    let (mut lit, mut sampled) = (0, 0);
+    for _ in 0..1000 {
+        let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Point::new(0.0, -1.0, 0.0));
+        rec.p = Point::random_in_unit_disk();
+        let emitted = mix.emitted(&ray, &rec);
+        match mix.scatter_record(&ray, &rec) {
+            // The light doesn't scatter but shines.
+            None => {
+                assert_eq!(emitted, 4.0 * white);
+                lit += 1;
+            }
+            Some(record) => {
+                assert_eq!(emitted, Point::default());
+                let Scattered::Pdf(pdf) = record.scattered else {
+                    panic!("a Lambertian scatters by a pdf");
+                };
+                let scattered = Ray::new(rec.p, pdf.generate());
+                assert!(mix.scattering_pdf(&ray, &rec, &scattered) > 0.0);
+                sampled += 1;
+            }
+        }
+    }
+    assert!(
+        lit > 400 && sampled > 400,
+        "{} lit, {} sampled",
+        lit,
+        sampled
+    );
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind, PerturbsNormal},
+    point::Point,
+    texture::Texture,
+};
+
+/// Shades `inner` with normals read from `map`, which encodes tangent-space
+/// normals as colors in the usual way: `(0.5, 0.5, 1.0)` is the unperturbed
+/// normal, and each channel maps `[0, 1]` to `[-1, 1]` along the tangent,
+/// the bitangent and the normal.
+pub struct NormalMapped {
+    inner: Arc<dyn Material>,
+    map: Arc<dyn Texture>,
+}
+
+impl NormalMapped {
+    pub fn new(inner: Arc<dyn Material>, map: Arc<dyn Texture>) -> Self {
+        Self { inner, map }
+    }
+}
+
+impl PerturbsNormal for NormalMapped {
+    const KIND: MaterialKind = MaterialKind::NormalMapped;
+
+    fn inner(&self) -> &dyn Material {
+        self.inner.as_ref()
+    }
+
+    fn perturbed(&self, rec: &HitRecord) -> HitRecord {
+        let encoded = self.map.value(rec.u, rec.v, &rec.p);
+        let local = 2.0 * encoded - Point::new(1.0, 1.0, 1.0);
+
+        let normal = rec.shading_frame().local(local);
+        rec.with_normal(normal.unit().unwrap_or(rec.normal))
+    }
+}
+
+#[test]
+fn tilts_the_normal_of_a_textured_sphere() {
+    use crate::{
+        hittable::{Hittable, Interval},
+        metal::Metal,
+        point::Vector,
+        ray::Ray,
+        sphere::Sphere,
+        texture::SolidColor,
+    };
+
+    let metal = Arc::new(Metal::new(Point::new(1.0, 1.0, 1.0), 0.0));
+    let tilt = 0.5 / 2.0_f64.sqrt();
+    let maps = [
+        (Point::new(0.5, 0.5, 1.0), Vector::new(0.0, 0.0, 1.0)),
+        (
+            Point::new(0.5 + tilt, 0.5, 0.5 + tilt),
+            Vector::new(1.0, 0.0, 0.0),
+        ),
+    ];
+    for (encoded, expected) in maps {
+        let mat = NormalMapped::new(metal.clone(), Arc::new(SolidColor::new(encoded)));
+        let sphere = Sphere::new(Point::default(), 1.0, Arc::new(mat));
+        let r = Ray::new(Point::new(0.0, 0.0, 5.0), Vector::new(0.0, 0.0, -1.0));
+        let rec = sphere
+            .hit(&r, &Interval::new_set_interval(0.001, f64::MAX))
+            .unwrap();
+
+        let (_, scattered) = rec.mat.scatter(&r, &rec).unwrap();
+        let direction = scattered.direction();
+        assert!((direction - expected).len() < 1e-9, "{:?}", direction);
+    }
+}
+
+#[test]
+fn samples_and_specularity_follow_the_inner_material() {
+    use crate::{
+        lambertian::Lambertian, material::Scattered, metal::Metal, point::Vector, ray::Ray,
+        texture::SolidColor,
+    };
+
+    // Tilted all the way to the tangent's side.
+    let map = Arc::new(SolidColor::new(Point::new(1.0, 0.5, 0.5)));
+    let lambertian = Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5)));
+    let mapped = NormalMapped::new(lambertian.clone(), map.clone());
+    let mut rec = HitRecord::new(lambertian);
+    rec.normal = Vector::new(0.0, 0.0, 1.0);
+    rec.tangent = Some(Vector::new(1.0, 0.0, 0.0));
+    let r_in = Ray::new(Point::new(0.0, 0.0, 1.0), Vector::new(0.0, 0.0, -1.0));
+
+    let record = mapped.scatter_record(&r_in, &rec).unwrap();
+    assert!(matches!(record.scattered, Scattered::Pdf(_)));
+    // Dense along the perturbed normal, not the surface's.
+    let along = |d| Ray::new(rec.p, d);
+    let pdf = |d| mapped.scattering_pdf(&r_in, &rec, &along(d));
+    assert!(pdf(Vector::new(1.0, 0.0, 0.0)) > 0.3);
+    assert_eq!(pdf(Vector::new(-1.0, 0.0, 0.0)), 0.0);
+
+    let mirror = NormalMapped::new(Arc::new(Metal::new(Point::new(1.0, 1.0, 1.0), 0.0)), map);
+    assert!(mirror.is_specular());
+    assert!(!mapped.is_specular());
+}
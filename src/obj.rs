@@ -0,0 +1,303 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    dielectric::Dielectric,
+    diffuse_light::DiffuseLight,
+    hittable::{Hittable, HittableList},
+    lambertian::Lambertian,
+    material::Material,
+    metal::Metal,
+    phong::Phong,
+    point::{Point, Vector},
+    triangle::Triangle,
+};
+
+/// Loads the faces of a Wavefront OBJ file as triangles.
+///
+/// Only `v`, `vn`, `f`, `mtllib` and `usemtl` statements are read; polygons
+/// with more than three vertices are fanned into triangles. Faces that
+/// reference normals (`v//vn` or `v/vt/vn`) on every vertex are smooth
+/// shaded. Material libraries are looked for next to the OBJ file and read
+/// with `load_mtl`; faces before any `usemtl`, or after one naming a
+/// material none of them define, get `mat`.
+pub fn load(path: impl AsRef<Path>, mat: Arc<dyn Material>) -> io::Result<HittableList> {
+    let path = path.as_ref();
+    parse_in(BufReader::new(File::open(path)?), mat, path.parent())
+}
+
+/// Like `load`, but with no file to find material libraries next to, so
+/// every face gets `mat`.
+pub fn parse(reader: impl BufRead, mat: Arc<dyn Material>) -> io::Result<HittableList> {
+    parse_in(reader, mat, None)
+}
+
+fn parse_in(
+    reader: impl BufRead,
+    mat: Arc<dyn Material>,
+    dir: Option<&Path>,
+) -> io::Result<HittableList> {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut materials = HashMap::new();
+    let mut current = mat.clone();
+    let mut world = HittableList::new(None);
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => positions.push(parse_point(tokens, number)?),
+            Some("vn") => normals.push(parse_point(tokens, number)?),
+            Some("mtllib") => {
+                if let Some(dir) = dir {
+                    for name in tokens {
+                        materials.extend(load_mtl(dir.join(name))?);
+                    }
+                }
+            }
+            Some("usemtl") => {
+                let name = tokens.next().unwrap_or_default();
+                current = materials.get(name).unwrap_or(&mat).clone();
+            }
+            Some("f") => {
+                let corners = tokens
+                    .map(|x| parse_corner(x, &positions, &normals, number))
+                    .collect::<io::Result<Vec<_>>>()?;
+                if corners.len() < 3 {
+                    return Err(invalid(number, "face with fewer than three vertices"));
+                }
+                world.extend((1..corners.len() - 1).map(|i| {
+                    let (a, b, c) = (corners[0], corners[i], corners[i + 1]);
+                    let triangle = match (a.1, b.1, c.1) {
+                        (Some(na), Some(nb), Some(nc)) => {
+                            Triangle::with_normals(a.0, b.0, c.0, [na, nb, nc], current.clone())
+                        }
+                        _ => Triangle::new(a.0, b.0, c.0, current.clone()),
+                    };
+                    Box::new(triangle) as Box<dyn Hittable>
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(world)
+}
+
+/// Reads the materials of a Wavefront MTL file by name, as `parse_mtl`.
+pub fn load_mtl(path: impl AsRef<Path>) -> io::Result<HashMap<String, Arc<dyn Material>>> {
+    parse_mtl(BufReader::new(File::open(path)?))
+}
+
+/// Builds a material for each `newmtl` from the properties the renderer has
+/// an equivalent for, ignoring the rest, such as texture maps:
+///
+/// - an emission `Ke` that isn't black makes a `DiffuseLight`;
+/// - a dissolve `d` under 1, a transparency `Tr` over 0 or an `illum` model
+///   of 4, 6 or 7 makes a `Dielectric` with index `Ni`, 1.5 if not given;
+/// - a specular `Ks` that isn't black makes a `Metal` without a diffuse
+///   `Kd`, fuzzier the lower the exponent `Ns`, and a `Phong` with one;
+/// - anything else is a `Lambertian` of `Kd`.
+pub fn parse_mtl(reader: impl BufRead) -> io::Result<HashMap<String, Arc<dyn Material>>> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlProperties)> = None;
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        let Some(statement) = tokens.next() else {
+            continue;
+        };
+        if statement == "newmtl" {
+            let name = tokens.collect::<Vec<_>>().join(" ");
+            if let Some((name, properties)) = current.replace((name, MtlProperties::default())) {
+                materials.insert(name, properties.material());
+            }
+            continue;
+        }
+        if !["Kd", "Ks", "Ke", "Ns", "Ni", "d", "Tr", "illum"].contains(&statement) {
+            continue;
+        }
+        let Some((_, properties)) = current.as_mut() else {
+            return Err(invalid(number, "material property before newmtl"));
+        };
+        let number_after = |mut tokens: std::str::SplitWhitespace| {
+            tokens
+                .next()
+                .and_then(|x| x.parse::<f64>().ok())
+                .ok_or_else(|| invalid(number, "malformed number"))
+        };
+        match statement {
+            "Kd" => properties.diffuse = Some(parse_color(tokens, number)?),
+            "Ks" => properties.specular = Some(parse_color(tokens, number)?),
+            "Ke" => properties.emission = Some(parse_color(tokens, number)?),
+            "Ns" => properties.exponent = Some(number_after(tokens)?),
+            "Ni" => properties.index = Some(number_after(tokens)?),
+            "d" => properties.dissolve = Some(number_after(tokens)?),
+            "Tr" => properties.dissolve = Some(1.0 - number_after(tokens)?),
+            _ => properties.illum = Some(number_after(tokens)? as u32),
+        }
+    }
+    if let Some((name, properties)) = current {
+        materials.insert(name, properties.material());
+    }
+    Ok(materials)
+}
+
+#[derive(Default)]
+struct MtlProperties {
+    diffuse: Option<Point>,
+    specular: Option<Point>,
+    emission: Option<Point>,
+    exponent: Option<f64>,
+    index: Option<f64>,
+    dissolve: Option<f64>,
+    illum: Option<u32>,
+}
+
+impl MtlProperties {
+    fn material(self) -> Arc<dyn Material> {
+        let black = |color: Option<Point>| color.is_none_or(|c| c.near_zero());
+        let diffuse = self.diffuse.unwrap_or_default();
+        if !black(self.emission) {
+            return Arc::new(DiffuseLight::new(self.emission.unwrap_or_default(), 1.0));
+        }
+        if self.dissolve.is_some_and(|d| d < 1.0) || matches!(self.illum, Some(4 | 6 | 7)) {
+            return Arc::new(Dielectric::new(self.index.unwrap_or(1.5)));
+        }
+        match self.specular {
+            // The Blinn-Phong exponent's usual match in roughness.
+            Some(specular) if !specular.near_zero() && black(self.diffuse) => Arc::new(Metal::new(
+                specular,
+                self.exponent
+                    .map_or(0.0, |n| (2.0 / (n.max(0.0) + 2.0)).sqrt()),
+            )),
+            Some(specular) if !specular.near_zero() => {
+                Arc::new(Phong::new(diffuse, specular, self.exponent.unwrap_or(10.0)))
+            }
+            _ => Arc::new(Lambertian::new(diffuse)),
+        }
+    }
+}
+
+/// Three channels, or one for a gray.
+fn parse_color<'a>(tokens: impl Iterator<Item = &'a str>, number: usize) -> io::Result<Point> {
+    let channels = tokens
+        .take(3)
+        .map(|x| x.parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid(number, "malformed color"))?;
+    match channels[..] {
+        [gray] => Ok(Point::new(gray, gray, gray)),
+        [r, g, b] => Ok(Point::new(r, g, b)),
+        _ => Err(invalid(number, "expected one or three channels")),
+    }
+}
+
+fn invalid(number: usize, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("line {}: {}", number + 1, message),
+    )
+}
+
+fn parse_point<'a>(tokens: impl Iterator<Item = &'a str>, number: usize) -> io::Result<Point> {
+    let coords = tokens
+        .take(3)
+        .map(|x| x.parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid(number, "malformed coordinate"))?;
+    match coords[..] {
+        [x, y, z] => Ok(Point::new(x, y, z)),
+        _ => Err(invalid(number, "expected three coordinates")),
+    }
+}
+
+fn parse_corner(
+    token: &str,
+    positions: &[Point],
+    normals: &[Vector],
+    number: usize,
+) -> io::Result<(Point, Option<Vector>)> {
+    let mut indices = token.split('/');
+    let position = lookup(indices.next(), positions, number)?
+        .ok_or_else(|| invalid(number, "face vertex without a position"))?;
+    let normal = lookup(indices.nth(1), normals, number)?;
+    Ok((position, normal))
+}
+
+fn lookup<T: Copy>(index: Option<&str>, items: &[T], number: usize) -> io::Result<Option<T>> {
+    let index = match index {
+        None | Some("") => return Ok(None),
+        Some(index) => index
+            .parse::<i64>()
+            .map_err(|_| invalid(number, "malformed index"))?,
+    };
+    let resolved = if index < 0 {
+        items.len() as i64 + index
+    } else {
+        index - 1
+    };
+    usize::try_from(resolved)
+        .ok()
+        .and_then(|i| items.get(i).copied())
+        .map(Some)
+        .ok_or_else(|| invalid(number, "index out of range"))
+}
+
+#[test]
+fn reads_vertex_normals_for_faces() {
+    use crate::{hittable::Interval, lambertian::Lambertian, ray::Ray};
+
+    let source = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvn 0 0 1\nf 1//1 2//1 3//1 4//1\n";
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let world = parse(source.as_bytes(), mat).unwrap();
+
+    let ray = Ray::new(Point::new(0.25, 0.75, 1.0), Vector::new(0.0, 0.0, -1.0));
+    let record = world
+        .hit(&ray, &Interval::new_set_interval(0.001, f64::MAX))
+        .unwrap();
+    assert_eq!(record.normal, Vector::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn assigns_faces_the_materials_of_their_library() {
+    use crate::{hittable::Interval, lambertian::Lambertian, material::MaterialKind, ray::Ray};
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/two_materials.obj");
+    let fallback = Arc::new(Lambertian::new(Point::default()));
+    let world = load(path, fallback).unwrap();
+
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let kind_at = |x| {
+        let ray = Ray::new(Point::new(x, 0.5, 1.0), Vector::new(0.0, 0.0, -1.0));
+        world.hit(&ray, &interval).unwrap().mat.kind()
+    };
+    assert_eq!(kind_at(0.5), MaterialKind::Lambertian);
+    assert_eq!(kind_at(2.5), MaterialKind::Metal);
+    assert_eq!(kind_at(4.5), MaterialKind::Lambertian);
+}
+
+#[test]
+fn mtl_properties_pick_the_material() {
+    use crate::material::MaterialKind;
+
+    let source = "newmtl matte\nKd 0.8 0.1 0.1\n\
+                  newmtl shiny\nKd 0.5\nKs 0.5 0.5 0.5\nNs 50\nmap_Kd wood.png\n\
+                  newmtl glass\nd 0.2\nNi 1.33\n\
+                  newmtl lamp\nKe 4 4 4\n";
+    let materials = parse_mtl(source.as_bytes()).unwrap();
+    let kind = |name| materials[name].kind();
+    assert_eq!(kind("matte"), MaterialKind::Lambertian);
+    assert_eq!(kind("shiny"), MaterialKind::Phong);
+    assert_eq!(kind("glass"), MaterialKind::Dielectric);
+    assert_eq!(kind("lamp"), MaterialKind::DiffuseLight);
+
+    assert!(parse_mtl("Kd 1 1 1\n".as_bytes()).is_err());
+}
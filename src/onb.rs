@@ -0,0 +1,82 @@
+use crate::point::{Point, Vector};
+
+/// A right-handed orthonormal basis `(u, v, w)`, for working in a frame
+/// around a normal `w`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Onb {
+    u: Vector,
+    v: Vector,
+    w: Vector,
+}
+
+impl Onb {
+    /// A basis around `n`, with `u` and `v` in some direction perpendicular
+    /// to it.
+    pub fn from_w(n: &Vector) -> Self {
+        let w = n.unit().unwrap_or(*n);
+        let helper = if w.x().abs() > 0.9 {
+            Vector::new(0.0, 1.0, 0.0)
+        } else {
+            Vector::new(1.0, 0.0, 0.0)
+        };
+        Self::with_u(w, w.cross(&helper))
+    }
+
+    /// A basis around `n` with `u` along `tangent` projected perpendicular to
+    /// `n`, or as `from_w` if `tangent` is parallel to `n`.
+    pub fn from_w_and_tangent(n: &Vector, tangent: &Vector) -> Self {
+        let w = n.unit().unwrap_or(*n);
+        let projected = *tangent - w * tangent.dot(&w);
+        if projected.unit().is_none() {
+            return Self::from_w(&w);
+        }
+        Self::with_u(w, projected)
+    }
+
+    fn with_u(w: Vector, u: Vector) -> Self {
+        let u = u.unit().unwrap_or_default();
+        Self {
+            u,
+            v: w.cross(&u),
+            w,
+        }
+    }
+
+    pub fn u(&self) -> Vector {
+        self.u
+    }
+
+    pub fn v(&self) -> Vector {
+        self.v
+    }
+
+    pub fn w(&self) -> Vector {
+        self.w
+    }
+
+    /// The world-space direction with coordinates `a` in this basis.
+    pub fn local(&self, a: Point) -> Vector {
+        a.x() * self.u + a.y() * self.v + a.z() * self.w
+    }
+}
+
+#[test]
+fn basis_is_orthonormal() {
+    let tangent = Vector::new(0.0, 1.0, 0.0);
+    for n in [
+        Vector::new(0.0, 0.0, 1.0),
+        Vector::new(1.0, 0.0, 0.0),
+        Vector::new(1.0, 1.0, 0.0),
+        Vector::new(-0.3, 2.0, 0.5),
+    ] {
+        for onb in [Onb::from_w(&n), Onb::from_w_and_tangent(&n, &tangent)] {
+            let (u, v, w) = (onb.u(), onb.v(), onb.w());
+            for x in [u, v, w] {
+                assert!((x.len() - 1.0).abs() < 1e-9);
+            }
+            assert!(u.dot(&v).abs() < 1e-9 && v.dot(&w).abs() < 1e-9 && w.dot(&u).abs() < 1e-9);
+            assert!((u.cross(&v) - w).len() < 1e-9);
+            assert!((onb.local(Point::new(0.0, 0.0, 2.0)) - 2.0 * w).len() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use std::f64::consts::PI;
+
+use crate::{
+    hittable::Hittable,
+    onb::Onb,
+    point::{Point, Vector},
+    utils::random_between,
+};
+
+/// A distribution of directions to importance sample bounces with.
+pub trait Pdf {
+    /// Density, over solid angle, of `generate` returning `direction`.
+    fn value(&self, direction: &Vector) -> f64;
+
+    fn generate(&self) -> Vector;
+}
+
+/// Cosine-weighted directions over the hemisphere around a normal, the
+/// shape of a Lambertian surface's scattering.
+pub struct CosinePdf {
+    frame: Onb,
+}
+
+impl CosinePdf {
+    pub fn new(normal: &Vector) -> Self {
+        Self {
+            frame: Onb::from_w(normal),
+        }
+    }
+}
+
+impl Pdf for CosinePdf {
+    fn value(&self, direction: &Vector) -> f64 {
+        let Some(direction) = direction.unit() else {
+            return 0.0;
+        };
+        (direction.dot(&self.frame.w()) / PI).max(0.0)
+    }
+
+    fn generate(&self) -> Vector {
+        let phi = 2.0 * PI * random_between(0.0, 1.0);
+        let r2 = random_between(0.0, 1.0);
+        self.frame.local(Point::new(
+            phi.cos() * r2.sqrt(),
+            phi.sin() * r2.sqrt(),
+            (1.0 - r2).sqrt(),
+        ))
+    }
+}
+
+/// Directions from `origin` toward `objects`, typically the lights.
+pub struct HittablePdf<'a> {
+    objects: &'a dyn Hittable,
+    origin: Point,
+}
+
+impl<'a> HittablePdf<'a> {
+    pub fn new(objects: &'a dyn Hittable, origin: Point) -> Self {
+        Self { objects, origin }
+    }
+}
+
+impl Pdf for HittablePdf<'_> {
+    fn value(&self, direction: &Vector) -> f64 {
+        self.objects.pdf_value(&self.origin, direction)
+    }
+
+    fn generate(&self) -> Vector {
+        self.objects.random(&self.origin)
+    }
+}
+
+/// How bright a sky that varies only with height is over the sphere of
+/// directions, tabulated in `SKY_BANDS` bands of height, to draw directions
+/// toward it with `SkyPdf`.
+pub struct SkyTable {
+    /// Density over solid angle in each band, from straight down up.
+    density: Vec<f64>,
+    /// Running share of the sphere's samples falling in each band and
+    /// those below it.
+    cdf: Vec<f64>,
+}
+
+const SKY_BANDS: usize = 64;
+
+impl SkyTable {
+    /// `luminance` gives the sky's brightness at direction height `y` from
+    /// -1 to 1. `None` if the sky is black.
+    pub fn new(luminance: impl Fn(f64) -> f64) -> Option<Self> {
+        let band = 2.0 / SKY_BANDS as f64;
+        let bright: Vec<f64> = (0..SKY_BANDS)
+            .map(|i| luminance(-1.0 + (i as f64 + 0.5) * band).max(0.0))
+            .collect();
+        let total: f64 = bright.iter().sum();
+        if total <= 0.0 || !total.is_finite() {
+            return None;
+        }
+        // Each band covers the same solid angle, 4 pi / SKY_BANDS.
+        let density = bright
+            .iter()
+            .map(|b| b / total * SKY_BANDS as f64 / (4.0 * PI))
+            .collect();
+        let cdf = bright
+            .iter()
+            .scan(0.0, |sum, b| {
+                *sum += b / total;
+                Some(*sum)
+            })
+            .collect();
+        Some(Self { density, cdf })
+    }
+
+    /// Density of `sample` returning the unit vector `direction`.
+    fn value(&self, direction: &Vector) -> f64 {
+        let band = ((direction.y() + 1.0) * 0.5 * SKY_BANDS as f64) as usize;
+        self.density[band.min(SKY_BANDS - 1)]
+    }
+
+    fn sample(&self) -> Vector {
+        let pick = random_between(0.0, 1.0);
+        let band = self.cdf.partition_point(|&c| c <= pick).min(SKY_BANDS - 1);
+        let y = -1.0 + (band as f64 + random_between(0.0, 1.0)) * 2.0 / SKY_BANDS as f64;
+        let phi = 2.0 * PI * random_between(0.0, 1.0);
+        let r = (1.0 - y * y).max(0.0).sqrt();
+        Vector::new(r * phi.cos(), y, r * phi.sin())
+    }
+}
+
+/// Directions over the hemisphere around a normal drawn as often as the
+/// sky of a `SkyTable` is bright there. Sky directions below the surface
+/// are mirrored above it rather than wasted.
+pub struct SkyPdf<'a> {
+    sky: &'a SkyTable,
+    normal: Vector,
+}
+
+impl<'a> SkyPdf<'a> {
+    pub fn new(sky: &'a SkyTable, normal: &Vector) -> Self {
+        Self {
+            sky,
+            normal: normal.unit().unwrap_or(Vector::new(0.0, 1.0, 0.0)),
+        }
+    }
+}
+
+impl Pdf for SkyPdf<'_> {
+    fn value(&self, direction: &Vector) -> f64 {
+        let Some(direction) = direction.unit() else {
+            return 0.0;
+        };
+        if direction.dot(&self.normal) <= 0.0 {
+            return 0.0;
+        }
+        self.sky.value(&direction) + self.sky.value(&direction.reflect(&self.normal))
+    }
+
+    fn generate(&self) -> Vector {
+        let direction = self.sky.sample();
+        if direction.dot(&self.normal) < 0.0 {
+            direction.reflect(&self.normal)
+        } else {
+            direction
+        }
+    }
+}
+
+/// An even mix of two distributions, so a bounce is drawn from either with
+/// equal chance and its density is the average of theirs.
+pub struct MixturePdf<'a> {
+    first: &'a dyn Pdf,
+    second: &'a dyn Pdf,
+}
+
+impl<'a> MixturePdf<'a> {
+    pub fn new(first: &'a dyn Pdf, second: &'a dyn Pdf) -> Self {
+        Self { first, second }
+    }
+}
+
+impl Pdf for MixturePdf<'_> {
+    fn value(&self, direction: &Vector) -> f64 {
+        0.5 * self.first.value(direction) + 0.5 * self.second.value(direction)
+    }
+
+    fn generate(&self) -> Vector {
+        if random_between(0.0, 1.0) < 0.5 {
+            self.first.generate()
+        } else {
+            self.second.generate()
+        }
+    }
+}
+
+#[test]
+fn cosine_value_matches_the_generated_directions() {
+    let normal = Vector::new(0.0, 0.6, 0.8);
+    let pdf = CosinePdf::new(&normal);
+
+    assert!((pdf.value(&normal) - 1.0 / PI).abs() < 1e-12);
+    assert_eq!(pdf.value(&-normal), 0.0);
+
+    // value is cos / pi, so the mean cosine of its samples is 2/3.
+    let samples = 20_000;
+    let mean = (0..samples)
+        .map(|_| {
+            let direction = pdf.generate();
+            assert!((pdf.value(&direction) - direction.dot(&normal) / PI).abs() < 1e-9);
+            direction.dot(&normal)
+        })
+        .sum::<f64>()
+        / f64::from(samples);
+    assert!((mean - 2.0 / 3.0).abs() < 0.01, "mean {}", mean);
+}
+
+#[test]
+fn hittable_pdf_aims_at_its_objects() {
+    use std::sync::Arc;
+
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let origin = Point::new(0.0, 0.0, 0.0);
+    let light = Sphere::new(
+        Point::new(0.0, 5.0, 0.0),
+        1.0,
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let pdf = HittablePdf::new(&light, origin);
+
+    let up = Vector::new(0.0, 1.0, 0.0);
+    assert_eq!(pdf.value(&up), light.pdf_value(&origin, &up));
+    assert_eq!(pdf.value(&-up), 0.0);
+    for _ in 0..100 {
+        assert!(pdf.value(&pdf.generate()) > 0.0);
+    }
+}
+
+#[test]
+fn mixture_averages_its_parts() {
+    use std::sync::Arc;
+
+    use crate::{lambertian::Lambertian, quad::Quad};
+
+    let light = Quad::new(
+        Point::new(-1.0, 2.0, -1.0),
+        Vector::new(2.0, 0.0, 0.0),
+        Vector::new(0.0, 0.0, 2.0),
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let cosine = CosinePdf::new(&Vector::new(0.0, 1.0, 0.0));
+    let toward_light = HittablePdf::new(&light, Point::default());
+    let mixture = MixturePdf::new(&cosine, &toward_light);
+
+    for direction in [
+        Vector::new(0.0, 1.0, 0.0),
+        Vector::new(0.3, 1.0, -0.2),
+        Vector::new(1.0, 0.1, 0.0),
+    ] {
+        let expected = 0.5 * cosine.value(&direction) + 0.5 * toward_light.value(&direction);
+        assert_eq!(mixture.value(&direction), expected);
+    }
+
+    let samples = 10_000;
+    let toward = (0..samples)
+        .filter(|_| light.pdf_value(&Point::default(), &mixture.generate()) > 0.0)
+        .count();
+    // Half the samples aim at the light, and some cosine ones hit it too.
+    assert!(
+        toward > samples / 2 && toward < samples * 3 / 4,
+        "{}",
+        toward
+    );
+}
+
+#[test]
+fn sky_pdf_favors_the_bright_sky() {
+    let sky = SkyTable::new(|y| if y > 0.0 { 9.0 } else { 1.0 }).unwrap();
+    assert!(SkyTable::new(|_| 0.0).is_none());
+    let up = Vector::new(0.0, 1.0, 0.0);
+    let side = Vector::new(1.0, 0.0, 0.0);
+
+    for normal in [up, side, Vector::new(0.0, -0.6, 0.8)] {
+        let pdf = SkyPdf::new(&sky, &normal);
+        assert_eq!(pdf.value(&-normal), 0.0);
+        // The density integrates to one over the sphere.
+        let samples = 20_000;
+        let integral = (0..samples)
+            .map(|_| pdf.value(&Point::random_in_unit_vector().unwrap()))
+            .sum::<f64>()
+            * 4.0
+            * PI
+            / f64::from(samples);
+        assert!((integral - 1.0).abs() < 0.03, "integral {}", integral);
+        for _ in 0..100 {
+            assert!(pdf.value(&pdf.generate()) > 0.0);
+        }
+    }
+
+    // Facing sideways, nine in ten samples go up into the bright half.
+    let pdf = SkyPdf::new(&sky, &side);
+    let samples = 20_000;
+    let upward = (0..samples).filter(|_| pdf.generate().y() > 0.0).count();
+    let share = upward as f64 / f64::from(samples);
+    assert!((share - 0.9).abs() < 0.01, "share {}", share);
+}
@@ -0,0 +1,133 @@
+use std::f64::consts::PI;
+
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind},
+    onb::Onb,
+    point::Point,
+    ray::Ray,
+    scene::Json,
+    utils::random_between,
+};
+
+pub struct Phong {
+    diffuse: Point,
+    specular: Point,
+    shininess: f64,
+}
+
+impl Phong {
+    pub fn new(diffuse: Point, specular: Point, shininess: f64) -> Self {
+        Self {
+            diffuse,
+            specular,
+            shininess,
+        }
+    }
+
+    fn specular_probability(&self) -> f64 {
+        let diffuse = self.diffuse.x() + self.diffuse.y() + self.diffuse.z();
+        let specular = self.specular.x() + self.specular.y() + self.specular.z();
+        if diffuse + specular <= 0.0 {
+            0.0
+        } else {
+            specular / (diffuse + specular)
+        }
+    }
+
+    fn sample_lobe(&self, axis: &Point) -> Point {
+        let frame = Onb::from_w(axis);
+        let cos_alpha = random_between(0.0, 1.0).powf(1.0 / (self.shininess + 1.0));
+        let sin_alpha = (1.0 - cos_alpha * cos_alpha).sqrt();
+        let phi = 2.0 * PI * random_between(0.0, 1.0);
+
+        frame.local(Point::new(
+            sin_alpha * phi.cos(),
+            sin_alpha * phi.sin(),
+            cos_alpha,
+        ))
+    }
+}
+
+impl Material for Phong {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::Phong
+    }
+
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
+        let p_specular = self.specular_probability();
+
+        if random_between(0.0, 1.0) < p_specular {
+            let reflected = r_in.unit_direction()?.reflect(&rec.normal);
+            let direction = self.sample_lobe(&reflected);
+            if direction.dot(&rec.normal) <= 0.0 {
+                return None;
+            }
+            Some(((self.specular / p_specular)?, Ray::new(rec.p, direction)))
+        } else {
+            let mut direction = rec.normal + Point::random_in_unit_vector()?;
+            if direction.near_zero() {
+                direction = rec.normal;
+            }
+            Some((
+                (self.diffuse / (1.0 - p_specular))?,
+                Ray::new(rec.p, direction),
+            ))
+        }
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "phong",
+            [
+                ("diffuse", self.diffuse.into()),
+                ("specular", self.specular.into()),
+                ("shininess", self.shininess.into()),
+            ],
+        ))
+    }
+}
+
+#[test]
+fn scatters_into_both_lobes_by_their_share() {
+    use crate::point::Vector;
+
+    let (diffuse, specular) = (Point::new(0.5, 0.1, 0.1), Point::new(0.1, 0.1, 0.1));
+    let mut rec = HitRecord::new(std::sync::Arc::new(Phong::new(diffuse, specular, 1.0)));
+    rec.normal = Vector::new(0.0, 1.0, 0.0);
+    let r_in = Ray::new(Point::new(-1.0, 1.0, 0.0), Vector::new(1.0, -1.0, 0.0));
+    let reflected = Vector::new(1.0, 1.0, 0.0).unit().unwrap();
+
+    // How often the specular lobe is picked and how tightly it gathers
+    // around the mirror direction.
+    let lobes = |shininess| {
+        let phong = Phong::new(diffuse, specular, shininess);
+        let p = phong.specular_probability();
+        assert!((p - 0.3).abs() < 1e-12);
+        let (mut picked, mut cosines) = (0, 0.0);
+        let samples = 20_000;
+        for _ in 0..samples {
+            let Some((attenuation, ray)) = phong.scatter(&r_in, &rec) else {
+                continue;
+            };
+            let direction = ray.unit_direction().unwrap();
+            if attenuation == (diffuse / (1.0 - p)).unwrap() {
+                continue;
+            }
+            assert_eq!(attenuation, (specular / p).unwrap());
+            assert!(direction.dot(&rec.normal) > 0.0);
+            picked += 1;
+            cosines += direction.dot(&reflected);
+        }
+        (
+            f64::from(picked) / f64::from(samples),
+            cosines / f64::from(picked),
+        )
+    };
+    let (rough, rough_cosine) = lobes(10.0);
+    let (shiny, shiny_cosine) = lobes(1000.0);
+    // Widely spread samples sometimes dip below the surface and are lost.
+    assert!(rough > 0.25 && rough < 0.31, "{}", rough);
+    assert!((shiny - 0.3).abs() < 0.02, "{}", shiny);
+    assert!(rough_cosine < shiny_cosine && shiny_cosine > 0.99);
+}
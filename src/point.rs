@@ -1,5 +1,8 @@
+use std::fmt;
 use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
+use radians::Deg64;
+
 use crate::utils::random_between;
 
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
@@ -20,6 +23,26 @@ impl From<Point> for image::Rgb<u8> {
     }
 }
 
+impl From<Point> for image::Rgb<u16> {
+    fn from(p: Point) -> Self {
+        let r = (p.x().clamp(0.0, 1.0) * 65535.0) as u16;
+        let g = (p.y().clamp(0.0, 1.0) * 65535.0) as u16;
+        let b = (p.z().clamp(0.0, 1.0) * 65535.0) as u16;
+        Self([r, g, b])
+    }
+}
+
+/// Formats as `x y z`, the way PPM writes pixels.
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(feature = "simd")]
+pub use crate::simd::dot;
+
+#[cfg(not(feature = "simd"))]
 pub fn dot(lhs: &Point, rhs: &Point) -> f64 {
     lhs.x() * rhs.x() + lhs.y() * rhs.y() + lhs.z() * rhs.z()
 }
@@ -36,17 +59,57 @@ pub fn reflect(lhs: &Point, rhs: &Point) -> Point {
     *lhs - *rhs * 2.0 * dot(lhs, rhs)
 }
 
-pub fn refract(lhs: &Point, rhs: &Point, etai_over_etat: f64) -> Point {
+/// Refracts the unit vector `lhs` through a surface with normal `rhs`, or
+/// `None` if the angle is too steep and all of it is reflected instead.
+pub fn refract(lhs: &Point, rhs: &Point, etai_over_etat: f64) -> Option<Point> {
     let cos_theta = dot(&-*lhs, rhs).min(1.0);
+    let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+    if etai_over_etat * sin_theta > 1.0 {
+        return None;
+    }
     let r_out_perp = etai_over_etat * (*lhs + *rhs * cos_theta);
     let r_out_parallel = *rhs * (-(1.0 - r_out_perp.len_squared()).abs().sqrt());
-    r_out_perp + r_out_parallel
+    Some(r_out_perp + r_out_parallel)
 }
 
 impl Point {
     pub fn new(x: f64, y: f64, z: f64) -> Self {
         Self { x, y, z }
     }
+
+    pub fn dot(&self, other: &Self) -> f64 {
+        dot(self, other)
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        cross(self, other)
+    }
+
+    pub fn reflect(&self, normal: &Self) -> Self {
+        reflect(self, normal)
+    }
+
+    pub fn refract(&self, normal: &Self, etai_over_etat: f64) -> Option<Self> {
+        refract(self, normal, etai_over_etat)
+    }
+
+    /// The angle to `other` in radians, from 0 to pi, or 0 if either has no
+    /// length.
+    pub fn angle_between(&self, other: &Self) -> f64 {
+        let lengths = self.len() * other.len();
+        if lengths == 0.0 {
+            return 0.0;
+        }
+        // Rounding can push the cosine of nearly parallel vectors past 1.
+        (self.dot(other) / lengths).clamp(-1.0, 1.0).acos()
+    }
+
+    /// The part of this vector along `other`, or zero if `other` has no
+    /// length.
+    pub fn project_onto(&self, other: &Self) -> Self {
+        (self.dot(other) * *other / other.len_squared()).unwrap_or_default()
+    }
+
     pub fn x(&self) -> f64 {
         self.x
     }
@@ -59,6 +122,14 @@ impl Point {
         self.z
     }
 
+    pub fn axis(&self, n: usize) -> f64 {
+        match n {
+            0 => self.x,
+            1 => self.y,
+            _ => self.z,
+        }
+    }
+
     pub fn x_mut(&mut self) -> &mut f64 {
         &mut self.x
     }
@@ -83,6 +154,18 @@ impl Point {
         *self / self.len()
     }
 
+    /// Normalizes in place, returning `false` (and leaving the point untouched)
+    /// when the length is zero.
+    pub fn normalize(&mut self) -> bool {
+        match self.unit() {
+            Some(unit) => {
+                *self = unit;
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn random() -> Self {
         Self {
             x: random_between(0.0, 1.0),
@@ -129,6 +212,70 @@ impl Point {
         }
     }
 
+    pub fn min(&self, other: &Self) -> Self {
+        Self {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+            z: self.z.min(other.z),
+        }
+    }
+
+    pub fn max(&self, other: &Self) -> Self {
+        Self {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+            z: self.z.max(other.z),
+        }
+    }
+
+    /// Rotated by `theta` about the x axis, counterclockwise looking down
+    /// the axis toward the origin, as `Mat4::rotation_x` does.
+    pub fn rotate_x(&self, theta: Deg64) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        Self::new(
+            self.x,
+            cos * self.y - sin * self.z,
+            sin * self.y + cos * self.z,
+        )
+    }
+
+    pub fn rotate_y(&self, theta: Deg64) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        Self::new(
+            cos * self.x + sin * self.z,
+            self.y,
+            -sin * self.x + cos * self.z,
+        )
+    }
+
+    pub fn rotate_z(&self, theta: Deg64) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        Self::new(
+            cos * self.x - sin * self.y,
+            sin * self.x + cos * self.y,
+            self.z,
+        )
+    }
+
+    /// Rotated by `theta` about `axis`, which needn't be a unit vector, by
+    /// Rodrigues' formula. Unchanged if `axis` is zero.
+    pub fn rotate_around_axis(&self, axis: &Self, theta: Deg64) -> Self {
+        let Some(k) = axis.unit() else {
+            return *self;
+        };
+        let (sin, cos) = theta.sin_cos();
+        *self * cos + k.cross(self) * sin + k * (k.dot(self) * (1.0 - cos))
+    }
+
+    /// The color as 8-bit channels, clamped to `[0, 1]` first.
+    pub fn as_color_bytes(&self) -> [u8; 3] {
+        image::Rgb::from(*self).0
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
     pub fn near_zero(&self) -> bool {
         let s = 1e-8;
         self.x.abs() < s && self.y.abs() < s && self.z.abs() < s
@@ -144,6 +291,7 @@ impl Point {
     }
 }
 
+#[cfg(not(feature = "simd"))]
 impl Add for Point {
     type Output = Self;
 
@@ -164,6 +312,7 @@ impl AddAssign for Point {
     }
 }
 
+#[cfg(not(feature = "simd"))]
 impl Sub for Point {
     type Output = Self;
 
@@ -184,6 +333,7 @@ impl SubAssign for Point {
     }
 }
 
+#[cfg(not(feature = "simd"))]
 impl Neg for Point {
     type Output = Self;
 
@@ -196,6 +346,7 @@ impl Neg for Point {
     }
 }
 
+#[cfg(not(feature = "simd"))]
 impl Mul<Point> for Point {
     type Output = Self;
 
@@ -208,6 +359,7 @@ impl Mul<Point> for Point {
     }
 }
 
+#[cfg(not(feature = "simd"))]
 impl Mul<f64> for Point {
     type Output = Self;
 
@@ -220,6 +372,7 @@ impl Mul<f64> for Point {
     }
 }
 
+#[cfg(not(feature = "simd"))]
 impl Mul<Point> for f64 {
     type Output = Point;
 
@@ -256,6 +409,74 @@ impl Div<f64> for Point {
     }
 }
 
+impl Add<&Point> for &Point {
+    type Output = Point;
+
+    fn add(self, rhs: &Point) -> Self::Output {
+        *self + *rhs
+    }
+}
+
+impl AddAssign<&Point> for Point {
+    fn add_assign(&mut self, rhs: &Point) {
+        *self += *rhs;
+    }
+}
+
+impl Sub<&Point> for &Point {
+    type Output = Point;
+
+    fn sub(self, rhs: &Point) -> Self::Output {
+        *self - *rhs
+    }
+}
+
+impl SubAssign<&Point> for Point {
+    fn sub_assign(&mut self, rhs: &Point) {
+        *self -= *rhs;
+    }
+}
+
+impl Neg for &Point {
+    type Output = Point;
+
+    fn neg(self) -> Self::Output {
+        -*self
+    }
+}
+
+impl Mul<&Point> for &Point {
+    type Output = Point;
+
+    fn mul(self, rhs: &Point) -> Self::Output {
+        *self * *rhs
+    }
+}
+
+impl Mul<f64> for &Point {
+    type Output = Point;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        *self * rhs
+    }
+}
+
+impl Mul<&Point> for f64 {
+    type Output = Point;
+
+    fn mul(self, rhs: &Point) -> Self::Output {
+        self * *rhs
+    }
+}
+
+impl Div<f64> for &Point {
+    type Output = Option<Point>;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        *self / rhs
+    }
+}
+
 #[test]
 fn can_create_a_point_default() {
     let point = Point::default();
@@ -310,10 +531,10 @@ fn can_mul_assign_scalar() {
 fn can_div_scalar() {
     match Point::new(6.0, 6.0, 6.0) / 2.0 {
         Some(point) => assert_eq!(point, Point::new(3.0, 3.0, 3.0)),
-        None => assert!(false),
+        None => panic!("division by non-zero scalar should succeed"),
     };
 
-    assert!(matches!(Point::new(1.0, 1.0, 1.0) / 0.0, None));
+    assert!((Point::new(1.0, 1.0, 1.0) / 0.0).is_none());
 }
 
 #[test]
@@ -338,6 +559,17 @@ fn can_calculate_length() {
     assert_eq!(Point::new(2.0, 2.0, 2.0).len_squared(), 12.0);
 }
 
+#[test]
+fn can_normalize_in_place() {
+    let mut point = Point::new(0.0, 3.0, 4.0);
+    assert!(point.normalize());
+    assert_eq!(point, Point::new(0.0, 0.6, 0.8));
+
+    let mut zero = Point::default();
+    assert!(!zero.normalize());
+    assert_eq!(zero, Point::default());
+}
+
 #[test]
 fn test_dot() {
     assert_eq!(
@@ -353,3 +585,106 @@ fn test_cross() {
         Point::default()
     );
 }
+
+#[test]
+fn methods_match_free_functions() {
+    let a = Point::new(1.0, 2.0, 3.0);
+    let b = Point::new(-2.0, 0.5, 1.0);
+    assert_eq!(a.dot(&b), dot(&a, &b));
+    assert_eq!(a.cross(&b), cross(&a, &b));
+    assert_eq!(a.reflect(&b), reflect(&a, &b));
+    assert_eq!(a.refract(&b, 1.5), refract(&a, &b, 1.5));
+}
+
+#[test]
+fn reference_ops_match_value_ops() {
+    let a = Point::new(1.0, 2.0, 3.0);
+    let b = Point::new(-0.5, 4.0, 2.0);
+    let (ra, rb) = (&a, &b);
+    assert_eq!(ra + rb, a + b);
+    assert_eq!(ra - rb, a - b);
+    assert_eq!(ra * rb, a * b);
+    assert_eq!(ra * 2.0, a * 2.0);
+    assert_eq!(2.0 * ra, 2.0 * a);
+    assert_eq!(-ra, -a);
+    assert_eq!(ra / 2.0, a / 2.0);
+
+    let mut c = a;
+    c += rb;
+    c -= ra;
+    assert_eq!(c, b);
+}
+
+#[test]
+fn displays_space_separated() {
+    let p = Point::new(1.0, 0.5, 0.0);
+    assert_eq!(p.to_string(), "1 0.5 0");
+    assert_eq!(p.as_color_bytes(), [255, 127, 0]);
+}
+
+#[test]
+fn rotations_turn_counterclockwise_about_their_axis() {
+    use radians::Deg;
+
+    let close = |a: Point, b: Point| (a - b).len() < 1e-12;
+    let quarter = Deg::new(90.0);
+    let x = Point::new(1.0, 0.0, 0.0);
+    let y = Point::new(0.0, 1.0, 0.0);
+    let z = Point::new(0.0, 0.0, 1.0);
+    assert!(close(x.rotate_z(quarter), y));
+    assert!(close(y.rotate_x(quarter), z));
+    assert!(close(z.rotate_y(quarter), x));
+
+    let p = Point::new(0.3, -1.2, 2.0);
+    let theta = Deg::new(37.0);
+    assert!(close(
+        p.rotate_around_axis(&(2.0 * z), theta),
+        p.rotate_z(theta)
+    ));
+    assert!(close(p.rotate_around_axis(&x, theta), p.rotate_x(theta)));
+    assert!(close(p.rotate_around_axis(&-y, theta), p.rotate_y(-theta)));
+    assert_eq!(p.rotate_around_axis(&Point::default(), theta), p);
+}
+
+#[test]
+fn refract_is_none_past_the_critical_angle() {
+    let normal = Vector::new(0.0, 1.0, 0.0);
+    // From glass into air the critical angle is about 41.8 degrees.
+    let steep = Vector::new(0.8, -0.6, 0.0);
+    assert_eq!(steep.refract(&normal, 1.5), None);
+    let shallow = Vector::new(0.6, -0.8, 0.0);
+    let refracted = shallow.refract(&normal, 1.5).unwrap();
+    assert!((refracted.len() - 1.0).abs() < 1e-12);
+    assert!((refracted.x() - 0.9).abs() < 1e-12);
+    // Into the denser medium there is always a way through.
+    assert!(steep.refract(&normal, 1.0 / 1.5).is_some());
+}
+
+#[test]
+fn angle_between_orthogonal_and_parallel_vectors() {
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    let x = Point::new(2.0, 0.0, 0.0);
+    assert_eq!(x.angle_between(&Point::new(0.0, 3.0, 0.0)), FRAC_PI_2);
+    assert_eq!(x.angle_between(&Point::new(0.1, 0.0, 0.0)), 0.0);
+    assert_eq!(x.angle_between(&-x), PI);
+    let v = Point::new(0.1, 0.2, 0.3);
+    // Rounding still leaves a sliver, but never a NaN.
+    assert!(v.angle_between(&(3.0 * v)) < 1e-7);
+    assert_eq!(x.angle_between(&Point::default()), 0.0);
+}
+
+#[test]
+fn project_onto_orthogonal_and_parallel_vectors() {
+    let v = Point::new(1.0, 2.0, 3.0);
+    assert_eq!(
+        v.project_onto(&Point::new(0.0, 4.0, 0.0)),
+        Point::new(0.0, 2.0, 0.0)
+    );
+    assert_eq!(
+        Point::new(0.0, 0.0, 5.0).project_onto(&Point::new(1.0, 0.0, 0.0)),
+        Point::default()
+    );
+    assert_eq!((2.0 * v).project_onto(&v), 2.0 * v);
+    assert_eq!(v.project_onto(&Point::default()), Point::default());
+}
@@ -0,0 +1,225 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable, Interval},
+    material::Material,
+    point::{Point, Vector},
+    ray::Ray,
+    scene::Json,
+    utils::random_between,
+};
+
+/// The parallelogram with corner `q` spanned by the edges `u` and `v`.
+pub struct Quad {
+    q: Point,
+    u: Vector,
+    v: Vector,
+    mat: Arc<dyn Material>,
+    normal: Vector,
+    d: f64,
+    w: Vector,
+    area: f64,
+    cull_backfaces: bool,
+}
+
+impl Quad {
+    pub fn new(q: Point, u: Vector, v: Vector, mat: Arc<dyn Material>) -> Self {
+        let n = u.cross(&v);
+        let normal = n.unit().unwrap_or_default();
+        Self {
+            q,
+            u,
+            v,
+            mat,
+            normal,
+            d: normal.dot(&q),
+            w: (n / n.len_squared()).unwrap_or_default(),
+            area: n.len(),
+            cull_backfaces: false,
+        }
+    }
+
+    /// Makes the quad invisible from behind, the side opposite its normal
+    /// `u × v`, so every hit that remains has `front_face` set.
+    pub fn with_backface_culling(mut self) -> Self {
+        self.cull_backfaces = true;
+        self
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let denom = self.normal.dot(&r.direction());
+        if denom.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = (self.d - self.normal.dot(&r.origin())) / denom;
+        if !ray_t.surrounds(t) {
+            return None;
+        }
+
+        let p = r.at(t);
+        let planar = p - self.q;
+        let alpha = self.w.dot(&planar.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        let front_face = r.direction().dot(&self.normal) < 0.0;
+        if self.cull_backfaces && !front_face {
+            return None;
+        }
+        Some(HitRecord {
+            t,
+            u: alpha,
+            v: beta,
+            p,
+            normal: if front_face {
+                self.normal
+            } else {
+                -self.normal
+            },
+            front_face,
+            tangent: self.u.unit(),
+            bitangent: self.v.unit(),
+            id: None,
+            mat: self.mat.clone(),
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let corners = [
+            self.q,
+            self.q + self.u,
+            self.q + self.v,
+            self.q + self.u + self.v,
+        ];
+        Some(Aabb::around_points(corners)?.padded(1e-4))
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        let center = self.q + 0.5 * (self.u + self.v);
+        let radius = 0.5 * (self.u + self.v).len().max((self.u - self.v).len());
+        Some((center, radius))
+    }
+
+    /// Uniform over the quad's area, converted to solid angle from `origin`.
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        let ray = Ray::new(*origin, *direction);
+        let Some(rec) = self.hit(&ray, &Interval::new_set_interval(0.001, f64::MAX)) else {
+            return 0.0;
+        };
+        let distance_squared = rec.t * rec.t * direction.len_squared();
+        let cosine = (direction.dot(&self.normal) / direction.len()).abs();
+        distance_squared / (cosine * self.area)
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        let p = self.q + random_between(0.0, 1.0) * self.u + random_between(0.0, 1.0) * self.v;
+        p - *origin
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "quad",
+            [
+                ("q", self.q.into()),
+                ("u", self.u.into()),
+                ("v", self.v.into()),
+                ("material", self.mat.to_json()?),
+                ("cull_backfaces", self.cull_backfaces.into()),
+            ],
+        ))
+    }
+}
+
+/// The six sides of the axis-aligned box with opposite corners `a` and `b`.
+pub fn make_box(a: Point, b: Point, mat: Arc<dyn Material>) -> Vec<Quad> {
+    let min = a.min(&b);
+    let max = a.max(&b);
+
+    let dx = Vector::new(max.x() - min.x(), 0.0, 0.0);
+    let dy = Vector::new(0.0, max.y() - min.y(), 0.0);
+    let dz = Vector::new(0.0, 0.0, max.z() - min.z());
+
+    vec![
+        Quad::new(Point::new(min.x(), min.y(), max.z()), dx, dy, mat.clone()),
+        Quad::new(Point::new(max.x(), min.y(), max.z()), -dz, dy, mat.clone()),
+        Quad::new(Point::new(max.x(), min.y(), min.z()), -dx, dy, mat.clone()),
+        Quad::new(Point::new(min.x(), min.y(), min.z()), dz, dy, mat.clone()),
+        Quad::new(Point::new(min.x(), max.y(), max.z()), dx, -dz, mat.clone()),
+        Quad::new(Point::new(min.x(), min.y(), min.z()), dx, dz, mat),
+    ]
+}
+
+#[test]
+fn quad_is_hit_only_inside_its_edges() {
+    use crate::lambertian::Lambertian;
+
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let quad = Quad::new(
+        Point::new(-1.0, -1.0, 0.0),
+        Vector::new(2.0, 0.0, 0.0),
+        Vector::new(0.0, 2.0, 0.0),
+        mat,
+    );
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let towards = |x: f64, y: f64| Ray::new(Point::new(x, y, 1.0), Vector::new(0.0, 0.0, -1.0));
+
+    let record = quad.hit(&towards(0.5, -0.5), &interval).unwrap();
+    assert!(record.front_face);
+    assert_eq!(record.t, 1.0);
+    assert_eq!(record.normal, Vector::new(0.0, 0.0, 1.0));
+    assert!(quad.hit(&towards(1.5, 0.0), &interval).is_none());
+}
+
+#[test]
+fn pdf_converts_area_to_solid_angle() {
+    use crate::lambertian::Lambertian;
+
+    let quad = Quad::new(
+        Point::new(-1.0, -1.0, -2.0),
+        Vector::new(2.0, 0.0, 0.0),
+        Vector::new(0.0, 2.0, 0.0),
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let origin = Point::default();
+    assert_eq!(quad.pdf_value(&origin, &Vector::new(0.0, 0.0, 1.0)), 0.0);
+    assert_eq!(quad.pdf_value(&origin, &Vector::new(2.0, 0.0, -1.0)), 0.0);
+    assert!((quad.pdf_value(&origin, &Vector::new(0.0, 0.0, -3.0)) - 1.0).abs() < 1e-12);
+
+    // Off-center, the quad is farther away and seen at a slant.
+    let direction = Vector::new(1.0, 0.0, -2.0);
+    let (distance_squared, cosine) = (5.0, 2.0 / 5.0_f64.sqrt());
+    let expected = distance_squared / (cosine * 4.0);
+    assert!((quad.pdf_value(&origin, &direction) - expected).abs() < 1e-9);
+
+    for _ in 0..100 {
+        assert!(quad.pdf_value(&origin, &quad.random(&origin)) > 0.0);
+    }
+}
+
+#[test]
+fn culled_quad_is_invisible_from_behind() {
+    use crate::lambertian::Lambertian;
+
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let quad = Quad::new(
+        Point::new(-1.0, -1.0, 0.0),
+        Vector::new(2.0, 0.0, 0.0),
+        Vector::new(0.0, 2.0, 0.0),
+        mat,
+    );
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let in_front = Ray::new(Point::new(0.0, 0.0, 1.0), Vector::new(0.0, 0.0, -1.0));
+    let behind = Ray::new(Point::new(0.0, 0.0, -1.0), Vector::new(0.0, 0.0, 1.0));
+
+    assert!(!quad.hit(&behind, &interval).unwrap().front_face);
+    let quad = quad.with_backface_culling();
+    assert!(quad.hit(&in_front, &interval).unwrap().front_face);
+    assert!(quad.hit(&behind, &interval).is_none());
+    assert_eq!(quad.pdf_value(&behind.origin(), &behind.direction()), 0.0);
+}
@@ -1,14 +1,71 @@
 use crate::point::{Point, Vector};
 
+/// A ray is expected to have a non-zero direction. Code consuming rays must
+/// not assume so, though: degenerate geometry can still produce one, and such
+/// rays are treated as carrying no light.
 #[derive(Default, Copy, PartialEq, Clone, Debug)]
 pub struct Ray {
     origin: Point,
     direction: Vector,
+    direction_len_squared: f64,
+    original_length: Option<f64>,
+    time: f64,
+    primary: bool,
 }
 
 impl Ray {
     pub fn new(origin: Point, direction: Vector) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            direction_len_squared: direction.len_squared(),
+            original_length: None,
+            time: 0.0,
+            primary: false,
+        }
+    }
+
+    /// A ray along the unit vector of `direction`, so hit distances `t` are
+    /// measured in world units and `at(t)` lands `t` units from the origin.
+    /// `unnormalized_t` converts such a `t` back to the parameter of the ray
+    /// `Ray::new(origin, direction)` would have built. Falls back to that ray
+    /// if `direction` cannot be normalized.
+    pub fn new_normalized(origin: Point, direction: Vector) -> Self {
+        let length = direction.len();
+        match direction / length {
+            Some(unit) if length.is_finite() => Self {
+                origin,
+                direction: unit,
+                direction_len_squared: 1.0,
+                original_length: Some(length),
+                time: 0.0,
+                primary: false,
+            },
+            _ => Self::new(origin, direction),
+        }
+    }
+
+    /// The same ray at `time`, for scenes that change over time. Rays start
+    /// at time 0.
+    pub fn with_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The same ray, marked as cast from the camera or not, for objects
+    /// that only some rays see. Rays start out unmarked.
+    pub fn with_primary(mut self, primary: bool) -> Self {
+        self.primary = primary;
+        self
+    }
+
+    /// Whether this is a camera ray, rather than a bounce or a probe.
+    pub fn is_primary(&self) -> bool {
+        self.primary
     }
 
     pub fn origin(&self) -> Point {
@@ -19,7 +76,65 @@ impl Ray {
         self.direction
     }
 
+    /// `direction().len_squared()`, computed once when the ray is built since
+    /// every object tested against the ray needs it.
+    pub fn direction_len_squared(&self) -> f64 {
+        self.direction_len_squared
+    }
+
+    /// The unit direction, without recomputing it for normalized rays.
+    pub fn unit_direction(&self) -> Option<Vector> {
+        match self.original_length {
+            Some(_) => Some(self.direction),
+            None => self.direction.unit(),
+        }
+    }
+
+    pub fn is_normalized(&self) -> bool {
+        self.original_length.is_some()
+    }
+
+    /// The parameter along the un-normalized direction that reaches the same
+    /// point as `t` along this ray.
+    pub fn unnormalized_t(&self, t: f64) -> f64 {
+        match self.original_length {
+            Some(length) => t / length,
+            None => t,
+        }
+    }
+
     pub fn at(&self, t: f64) -> Point {
         self.origin + t * self.direction
     }
 }
+
+#[test]
+fn normalized_ray_hits_the_same_points() {
+    use std::sync::Arc;
+
+    use crate::{
+        hittable::{Hittable, Interval},
+        lambertian::Lambertian,
+        sphere::Sphere,
+    };
+
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let sphere = Sphere::new(Point::new(0.5, 0.0, -4.0), 1.5, mat);
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+
+    let origin = Point::new(0.0, 0.2, 1.0);
+    let direction = Vector::new(0.1, -0.05, -3.0);
+    let plain = Ray::new(origin, direction);
+    let normalized = Ray::new_normalized(origin, direction);
+    assert!(normalized.is_normalized());
+    assert_eq!(normalized.direction_len_squared(), 1.0);
+
+    let expected = sphere.hit(&plain, &interval).unwrap();
+    let record = sphere.hit(&normalized, &interval).unwrap();
+    assert!((record.p - expected.p).len() < 1e-9);
+    assert!((record.t - expected.t * direction.len()).abs() < 1e-9);
+    assert!((normalized.unnormalized_t(record.t) - expected.t).abs() < 1e-9);
+    assert!((normalized.at(record.t) - plain.at(expected.t)).len() < 1e-9);
+
+    assert!(!Ray::new_normalized(origin, Vector::default()).is_normalized());
+}
@@ -0,0 +1,940 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    camera::{Background, Camera, CameraInit, PixelFilter},
+    dielectric::Dielectric,
+    diffuse_light::DiffuseLight,
+    hittable::{Hittable, HittableList},
+    id_tag::IdTag,
+    isotropic::Isotropic,
+    lambertian::Lambertian,
+    material::Material,
+    matrix::Mat4,
+    metal::Metal,
+    mix::Mix,
+    phong::Phong,
+    point::Point,
+    quad::Quad,
+    shadow_catcher::ShadowCatcher,
+    sphere::{Sphere, SphereCap},
+    texture::{CheckerTexture, SolidColor, Texture},
+    transform::{Scale, Transform},
+    triangle::Triangle,
+    two_sided::TwoSided,
+    unlit::Unlit,
+    visibility::Visibility,
+};
+
+/// A value in a scene file, which is JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys in the order they are written.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// An object with a `"type"` of `kind` followed by `fields`, the shape
+    /// scene files give everything that comes in kinds.
+    pub fn tagged(kind: &str, fields: impl IntoIterator<Item = (&'static str, Json)>) -> Self {
+        let kind = ("type".to_string(), Self::from(kind));
+        let fields = fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value));
+        Self::Object(std::iter::once(kind).chain(fields).collect())
+    }
+
+    /// The value under `key`, if this is an object that has one.
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        match self {
+            Self::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut parser = Parser {
+            text: text.as_bytes(),
+            pos: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.text.len() {
+            return Err(parser.error("the end of the file"));
+        }
+        Ok(value)
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        let pad = "  ".repeat(indent + 1);
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{}", b),
+            // Debug prints the shortest digits that read back the same.
+            Self::Number(x) if x.is_finite() => write!(f, "{:?}", x),
+            Self::Number(_) => write!(f, "null"),
+            Self::String(s) => write_string(f, s),
+            Self::Array(items) if items.is_empty() => write!(f, "[]"),
+            // Points and matrix rows stay on one line.
+            Self::Array(items) if items.iter().all(|x| matches!(x, Self::Number(_))) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.write(f, indent)?;
+                }
+                write!(f, "]")
+            }
+            Self::Array(items) => {
+                writeln!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    write!(f, "{}", pad)?;
+                    item.write(f, indent + 1)?;
+                    writeln!(f, "{}", if i + 1 < items.len() { "," } else { "" })?;
+                }
+                write!(f, "{}]", &pad[2..])
+            }
+            Self::Object(fields) if fields.is_empty() => write!(f, "{{}}"),
+            Self::Object(fields) => {
+                writeln!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    write!(f, "{}", pad)?;
+                    write_string(f, key)?;
+                    write!(f, ": ")?;
+                    value.write(f, indent + 1)?;
+                    writeln!(f, "{}", if i + 1 < fields.len() { "," } else { "" })?;
+                }
+                write!(f, "{}}}", &pad[2..])
+            }
+        }
+    }
+}
+
+/// Indented over several lines, to be easy to edit by hand.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(x: f64) -> Self {
+        Self::Number(x)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_string())
+    }
+}
+
+impl From<Point> for Json {
+    fn from(p: Point) -> Self {
+        Self::Array(vec![p.x().into(), p.y().into(), p.z().into()])
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", u32::from(c))?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, expected: &str) -> io::Error {
+        invalid(format!("expected {} at byte {}", expected, self.pos))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.text.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace and then `byte` if it comes next.
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.text.get(self.pos) == Some(&byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, byte: u8) -> io::Result<()> {
+        if self.eat(byte) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", char::from(byte))))
+        }
+    }
+
+    fn value(&mut self) -> io::Result<Json> {
+        self.skip_whitespace();
+        match self.text.get(self.pos) {
+            Some(b'{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                if !self.eat(b'}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(b':')?;
+                        fields.push((key, self.value()?));
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Ok(Json::Object(fields))
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                if !self.eat(b']') {
+                    loop {
+                        items.push(self.value()?);
+                        if self.eat(b']') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Ok(Json::Array(items))
+            }
+            Some(b'"') => self.string().map(Json::String),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'n') => self.literal("null", Json::Null),
+            _ => self.number(),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> io::Result<Json> {
+        if self.text[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(self.error(word))
+        }
+    }
+
+    fn number(&mut self) -> io::Result<Json> {
+        let start = self.pos;
+        while self
+            .text
+            .get(self.pos)
+            .is_some_and(|b| b.is_ascii_digit() || b"+-.eE".contains(b))
+        {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.text[start..self.pos])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .map(Json::Number)
+            .ok_or_else(|| {
+                self.pos = start;
+                self.error("a value")
+            })
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        if self.text.get(self.pos) != Some(&b'"') {
+            return Err(self.error("a string"));
+        }
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        loop {
+            let Some(&byte) = self.text.get(self.pos) else {
+                return Err(self.error("the end of the string"));
+            };
+            self.pos += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let code = self.text.get(self.pos).copied();
+                    self.pos += 1;
+                    let escaped = match code {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => self.unicode_escape()?,
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.error("an escape"));
+                        }
+                    };
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                _ => bytes.push(byte),
+            }
+        }
+        // Only split around ASCII, so still valid.
+        String::from_utf8(bytes).map_err(|_| invalid("a string isn't UTF-8".to_string()))
+    }
+
+    /// The character of a `\u` escape whose hex digits start at `pos`,
+    /// joining the two halves of a surrogate pair.
+    fn unicode_escape(&mut self) -> io::Result<char> {
+        let high = self.hex_digits()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if !self.text[self.pos..].starts_with(b"\\u") {
+                return Err(self.error("the second half of a surrogate pair"));
+            }
+            self.pos += 2;
+            let low = self.hex_digits()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("the second half of a surrogate pair"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("a character"))
+    }
+
+    fn hex_digits(&mut self) -> io::Result<u32> {
+        let code = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| self.error("four hex digits"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn field<'a>(json: &'a Json, key: &str) -> io::Result<&'a Json> {
+    json.get(key)
+        .ok_or_else(|| invalid(format!("missing \"{}\"", key)))
+}
+
+fn as_number(json: &Json) -> io::Result<f64> {
+    match json {
+        Json::Number(x) => Ok(*x),
+        _ => Err(invalid(format!("expected a number, not {}", json))),
+    }
+}
+
+fn as_point(json: &Json) -> io::Result<Point> {
+    match json {
+        Json::Array(items) if items.len() == 3 => Ok(Point::new(
+            as_number(&items[0])?,
+            as_number(&items[1])?,
+            as_number(&items[2])?,
+        )),
+        _ => Err(invalid(format!("expected a point, not {}", json))),
+    }
+}
+
+fn as_array(json: &Json) -> io::Result<&[Json]> {
+    match json {
+        Json::Array(items) => Ok(items),
+        _ => Err(invalid(format!("expected an array, not {}", json))),
+    }
+}
+
+fn number(json: &Json, key: &str) -> io::Result<f64> {
+    as_number(field(json, key)?)
+}
+
+fn optional_number(json: &Json, key: &str) -> io::Result<Option<f64>> {
+    json.get(key).map(as_number).transpose()
+}
+
+fn point(json: &Json, key: &str) -> io::Result<Point> {
+    as_point(field(json, key)?)
+}
+
+/// False if missing.
+fn flag(json: &Json, key: &str) -> io::Result<bool> {
+    match json.get(key) {
+        None => Ok(false),
+        Some(Json::Bool(b)) => Ok(*b),
+        Some(other) => Err(invalid(format!("expected true or false, not {}", other))),
+    }
+}
+
+fn count(json: &Json, key: &str) -> io::Result<u32> {
+    let x = number(json, key)?;
+    if x >= 0.0 && x <= f64::from(u32::MAX) && x.fract() == 0.0 {
+        Ok(x as u32)
+    } else {
+        Err(invalid(format!(
+            "\"{}\" must be a whole number, not {}",
+            key, x
+        )))
+    }
+}
+
+fn optional_count(json: &Json, key: &str) -> io::Result<Option<u32>> {
+    json.get(key).map(|_| count(json, key)).transpose()
+}
+
+fn kind(json: &Json) -> io::Result<&str> {
+    match field(json, "type")? {
+        Json::String(kind) => Ok(kind),
+        other => Err(invalid(format!("expected a type name, not {}", other))),
+    }
+}
+
+fn unknown(what: &str, kind: &str) -> io::Error {
+    invalid(format!("unknown {} type \"{}\"", what, kind))
+}
+
+fn unsaveable(what: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("the scene has a {} that can't be saved", what),
+    )
+}
+
+fn texture(json: &Json) -> io::Result<Arc<dyn Texture>> {
+    Ok(match kind(json)? {
+        "solid" => Arc::new(SolidColor::new(point(json, "color")?)),
+        "checker" => Arc::new(CheckerTexture::new(
+            texture(field(json, "even")?)?,
+            texture(field(json, "odd")?)?,
+            number(json, "scale")?,
+        )),
+        other => return Err(unknown("texture", other)),
+    })
+}
+
+fn material(json: &Json) -> io::Result<Arc<dyn Material>> {
+    Ok(match kind(json)? {
+        "lambertian" => {
+            let lambertian = Lambertian::with_texture(texture(field(json, "albedo")?)?);
+            if flag(json, "cosine_sampling")? {
+                Arc::new(lambertian.with_cosine_sampling())
+            } else {
+                Arc::new(lambertian)
+            }
+        }
+        "metal" => match json.get("roughness") {
+            Some(roughness) => Arc::new(Metal::with_roughness(
+                point(json, "color")?,
+                texture(roughness)?,
+            )),
+            None => Arc::new(Metal::new(point(json, "color")?, number(json, "fuzz")?)),
+        },
+        "dielectric" => Arc::new(Dielectric::new(number(json, "refraction_index")?)),
+        "diffuse_light" => Arc::new(DiffuseLight::with_texture(
+            texture(field(json, "emit")?)?,
+            number(json, "intensity")?,
+        )),
+        "unlit" => Arc::new(Unlit::with_texture(texture(field(json, "color")?)?)),
+        "shadow_catcher" => Arc::new(ShadowCatcher::with_texture(texture(field(
+            json, "albedo",
+        )?)?)),
+        "two_sided" => Arc::new(TwoSided::new(
+            material(field(json, "front")?)?,
+            material(field(json, "back")?)?,
+        )),
+        "phong" => Arc::new(Phong::new(
+            point(json, "diffuse")?,
+            point(json, "specular")?,
+            number(json, "shininess")?,
+        )),
+        "mix" => Arc::new(Mix::new(
+            material(field(json, "first")?)?,
+            material(field(json, "second")?)?,
+            number(json, "weight")?,
+        )),
+        "isotropic" => Arc::new(Isotropic::with_texture(texture(field(json, "albedo")?)?)),
+        other => return Err(unknown("material", other)),
+    })
+}
+
+fn list(json: &Json) -> io::Result<HittableList> {
+    let objects = as_array(field(json, "objects")?)?
+        .iter()
+        .map(hittable)
+        .collect::<io::Result<_>>()?;
+    let mut list = HittableList::new(Some(objects));
+    if flag(json, "sorted")? {
+        list.sort_along_dominant_axis();
+    }
+    Ok(list)
+}
+
+fn hittable(json: &Json) -> io::Result<Box<dyn Hittable>> {
+    let mat = || material(field(json, "material")?);
+    Ok(match kind(json)? {
+        "sphere" => {
+            let (center, radius) = (point(json, "center")?, number(json, "radius")?);
+            match json.get("to") {
+                Some(to) => Box::new(Sphere::moving(center, as_point(to)?, radius, mat()?)),
+                None => Box::new(Sphere::new(center, radius, mat()?)),
+            }
+        }
+        "sphere_cap" => Box::new(
+            SphereCap::new(
+                point(json, "center")?,
+                number(json, "radius")?,
+                (number(json, "polar_min")?, number(json, "polar_max")?),
+                mat()?,
+            )
+            .with_azimuth((number(json, "azimuth_min")?, number(json, "azimuth_max")?)),
+        ),
+        "quad" => {
+            let quad = Quad::new(
+                point(json, "q")?,
+                point(json, "u")?,
+                point(json, "v")?,
+                mat()?,
+            );
+            if flag(json, "cull_backfaces")? {
+                Box::new(quad.with_backface_culling())
+            } else {
+                Box::new(quad)
+            }
+        }
+        "triangle" => {
+            let points = |key| match as_array(field(json, key)?)? {
+                [a, b, c] => Ok([as_point(a)?, as_point(b)?, as_point(c)?]),
+                _ => Err(invalid(format!("\"{}\" must hold three points", key))),
+            };
+            let [a, b, c] = points("vertices")?;
+            match json.get("normals") {
+                Some(_) => Box::new(Triangle::with_normals(a, b, c, points("normals")?, mat()?)),
+                None => Box::new(Triangle::new(a, b, c, mat()?)),
+            }
+        }
+        "list" => Box::new(list(json)?),
+        "transform" => {
+            let rows = as_array(field(json, "matrix")?)?;
+            let mut m = [[0.0; 4]; 4];
+            if rows.len() != 4 {
+                return Err(invalid("a matrix must have four rows".to_string()));
+            }
+            for (row, json) in m.iter_mut().zip(rows) {
+                let values = as_array(json)?;
+                if values.len() != 4 {
+                    return Err(invalid("a matrix row must have four values".to_string()));
+                }
+                for (x, json) in row.iter_mut().zip(values) {
+                    *x = as_number(json)?;
+                }
+            }
+            let object = hittable(field(json, "object")?)?;
+            Box::new(
+                Transform::new(object, Mat4::new(m))
+                    .ok_or_else(|| invalid("a transform's matrix isn't invertible".to_string()))?,
+            )
+        }
+        "scale" => {
+            let object = hittable(field(json, "object")?)?;
+            Box::new(
+                Scale::new(object, point(json, "factors")?)
+                    .ok_or_else(|| invalid("a scale factor is zero".to_string()))?,
+            )
+        }
+        "id_tag" => Box::new(IdTag::new(
+            count(json, "id")?,
+            hittable(field(json, "object")?)?,
+        )),
+        "visibility" => Box::new(Visibility::new(
+            hittable(field(json, "object")?)?,
+            flag(json, "camera")?,
+            flag(json, "shadows")?,
+        )),
+        other => return Err(unknown("object", other)),
+    })
+}
+
+fn camera_to_json(camera: &Camera) -> io::Result<Json> {
+    let init = camera.settings();
+    let (width, height) = camera.dimensions();
+    let background = match &init.background {
+        Background::Gradient { top, bottom, power } => Json::tagged(
+            "gradient",
+            [
+                ("top", (*top).into()),
+                ("bottom", (*bottom).into()),
+                ("power", (*power).into()),
+            ],
+        ),
+        Background::Solid(color) => Json::tagged("solid", [("color", (*color).into())]),
+        Background::CubeMap(_) => return Err(unsaveable("cube map background")),
+    };
+    let filter = match init.pixel_filter {
+        PixelFilter::Box => "box",
+        PixelFilter::Tent => "tent",
+        PixelFilter::Gaussian => "gaussian",
+    };
+    let mut fields = vec![
+        ("width", f64::from(width).into()),
+        ("height", f64::from(height).into()),
+        ("vfov", init.vfov.into()),
+        ("lookfrom", init.lookfrom.into()),
+        ("lookat", init.lookat.into()),
+        ("vup", init.vup.into()),
+        ("focus_dist", init.focus_dist.into()),
+        ("defocus_angle", init.defocus_angle.into()),
+        (
+            "samples_per_pixel",
+            f64::from(init.samples_per_pixel).into(),
+        ),
+        ("pixel_filter", filter.into()),
+        ("background", background),
+        ("pixel_centers", init.pixel_centers.into()),
+        ("exposure", init.exposure.into()),
+        ("auto_exposure", init.auto_exposure.into()),
+        ("sky_sampling", init.sky_sampling.into()),
+        ("alpha_background", init.alpha_background.into()),
+        ("quiet", init.quiet.into()),
+        ("time", init.time.into()),
+        ("shutter_open", init.shutter_open.into()),
+        ("shutter_close", init.shutter_close.into()),
+    ];
+    let optional = [
+        ("aa_samples", init.aa_samples.map(f64::from)),
+        ("path_samples", init.path_samples.map(f64::from)),
+        ("near", init.near),
+        ("far", init.far),
+        ("ray_epsilon", init.ray_epsilon),
+        (
+            "max_specular_bounces",
+            init.max_specular_bounces.map(f64::from),
+        ),
+        ("denoise", init.denoise),
+        ("vignette", init.vignette),
+        ("max_depth", init.max_depth.map(f64::from)),
+    ];
+    fields.extend(
+        optional
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?.into()))),
+    );
+    Ok(Json::Object(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    ))
+}
+
+fn camera(json: &Json) -> io::Result<Camera> {
+    let background = field(json, "background")?;
+    let background = match kind(background)? {
+        "gradient" => Background::Gradient {
+            top: point(background, "top")?,
+            bottom: point(background, "bottom")?,
+            power: number(background, "power")?,
+        },
+        "solid" => Background::Solid(point(background, "color")?),
+        other => return Err(unknown("background", other)),
+    };
+    let pixel_filter = match field(json, "pixel_filter")? {
+        Json::String(name) if name == "box" => PixelFilter::Box,
+        Json::String(name) if name == "tent" => PixelFilter::Tent,
+        Json::String(name) if name == "gaussian" => PixelFilter::Gaussian,
+        other => return Err(invalid(format!("unknown pixel filter {}", other))),
+    };
+    let init = CameraInit {
+        vfov: number(json, "vfov")?,
+        lookfrom: point(json, "lookfrom")?,
+        lookat: point(json, "lookat")?,
+        vup: point(json, "vup")?,
+        focus_dist: number(json, "focus_dist")?,
+        defocus_angle: number(json, "defocus_angle")?,
+        samples_per_pixel: count(json, "samples_per_pixel")?,
+        aa_samples: optional_count(json, "aa_samples")?,
+        path_samples: optional_count(json, "path_samples")?,
+        pixel_filter,
+        background,
+        pixel_centers: flag(json, "pixel_centers")?,
+        near: optional_number(json, "near")?,
+        far: optional_number(json, "far")?,
+        ray_epsilon: optional_number(json, "ray_epsilon")?,
+        max_specular_bounces: optional_count(json, "max_specular_bounces")?,
+        denoise: optional_number(json, "denoise")?,
+        vignette: optional_number(json, "vignette")?,
+        max_depth: optional_count(json, "max_depth")?,
+        exposure: number(json, "exposure")?,
+        auto_exposure: flag(json, "auto_exposure")?,
+        sky_sampling: flag(json, "sky_sampling")?,
+        alpha_background: flag(json, "alpha_background")?,
+        quiet: flag(json, "quiet")?,
+        time: number(json, "time")?,
+        shutter_open: number(json, "shutter_open")?,
+        shutter_close: number(json, "shutter_close")?,
+        ..Default::default()
+    };
+    Ok(Camera::with_size(
+        count(json, "width")?,
+        count(json, "height")?,
+        init,
+    ))
+}
+
+/// `camera` and `world` as a scene file. The camera keeps its view, samples
+/// and background, but not how it writes images or runs, such as
+/// `quiet`, `threads` or `jpeg_quality`. Fails on anything that can't be
+/// written, which `to_json` reports as `None`, such as image textures,
+/// cube map backgrounds and animated materials.
+pub fn to_json(camera: &Camera, world: &HittableList) -> io::Result<Json> {
+    let world = world
+        .to_json()
+        .ok_or_else(|| unsaveable("object or material"))?;
+    let mut fields = vec![
+        ("camera".to_string(), camera_to_json(camera)?),
+        ("world".to_string(), world),
+    ];
+    if let Some(lights) = camera.lights() {
+        let lights = lights.to_json().ok_or_else(|| unsaveable("light"))?;
+        fields.push(("lights".to_string(), lights));
+    }
+    Ok(Json::Object(fields))
+}
+
+/// The camera and world of a scene file written by `to_json`. Materials
+/// shared between objects come back as a copy for each.
+pub fn from_json(json: &Json) -> io::Result<(Camera, HittableList)> {
+    let mut camera = camera(field(json, "camera")?)?;
+    if let Some(lights) = json.get("lights") {
+        camera = camera.with_lights(list(lights)?);
+    }
+    Ok((camera, list(field(json, "world")?)?))
+}
+
+/// Writes the scene to `path`; see `to_json`.
+pub fn save(camera: &Camera, world: &HittableList, path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, format!("{}\n", to_json(camera, world)?))
+}
+
+/// Reads a scene written by `save`.
+pub fn load(path: impl AsRef<Path>) -> io::Result<(Camera, HittableList)> {
+    from_json(&Json::parse(&fs::read_to_string(path)?)?)
+}
+
+/// Loads and renders every `.json` scene in `dir`, in name order, saving
+/// each as a PNG of the same name in `out_dir`. A scene that fails to load
+/// or save is skipped for the rest; the failures are returned with the
+/// scene they happened to, and only failing to list `dir` fails it all.
+/// Scenes saved from a `quiet` camera render without printing progress.
+pub fn render_all(
+    dir: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> io::Result<Vec<(PathBuf, io::Error)>> {
+    let mut scenes = fs::read_dir(dir)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    scenes.retain(|path| path.extension().is_some_and(|x| x == "json"));
+    scenes.sort();
+
+    let mut failures = Vec::new();
+    for scene in scenes {
+        let output = out_dir
+            .as_ref()
+            .join(scene.with_extension("png").file_name().unwrap_or_default());
+        let rendered = load(&scene).and_then(|(camera, mut world)| {
+            world.sort_along_dominant_axis();
+            camera.render_to(&world, output).map_err(io::Error::other)
+        });
+        if let Err(error) = rendered {
+            failures.push((scene, error));
+        }
+    }
+    Ok(failures)
+}
+
+#[test]
+fn parses_what_it_writes() {
+    let json = Json::Object(vec![
+        ("name".to_string(), "a \"quoted\"\tname\n".into()),
+        ("empty".to_string(), Json::Array(Vec::new())),
+        (
+            "values".to_string(),
+            Json::Array(vec![
+                Json::Null,
+                true.into(),
+                0.1.into(),
+                (-2.5e-12).into(),
+                f64::MAX.into(),
+                Point::new(1.0, 2.0, 3.0).into(),
+            ]),
+        ),
+    ]);
+    assert_eq!(Json::parse(&json.to_string()).unwrap(), json);
+
+    let escapes = Json::parse(r#" ["\u00e9\ud83d\ude00\/", -1E2] "#).unwrap();
+    assert_eq!(escapes, Json::Array(vec!["é😀/".into(), (-100.0).into()]));
+    for broken in ["", "[1, 2", "{\"a\" 1}", "[1] 2", "\"open", "nul", "[1,]"] {
+        assert!(Json::parse(broken).is_err(), "{:?} parsed", broken);
+    }
+}
+
+#[test]
+fn saved_scenes_load_and_render_the_same() {
+    use crate::{animated::Animated, point::Vector};
+
+    let camera = Camera::with_size(
+        12,
+        8,
+        CameraInit {
+            vfov: 60.0,
+            lookfrom: Point::new(0.0, 1.0, 3.0),
+            lookat: Point::new(0.0, 0.5, 0.0),
+            vup: Vector::new(0.0, 1.0, 0.0),
+            focus_dist: 3.0,
+            samples_per_pixel: 4,
+            pixel_filter: PixelFilter::Tent,
+            quiet: true,
+            ..Default::default()
+        },
+    );
+    let light = || {
+        Quad::new(
+            Point::new(-1.0, 3.0, -1.0),
+            Vector::new(2.0, 0.0, 0.0),
+            Vector::new(0.0, 0.0, 2.0),
+            Arc::new(DiffuseLight::new(Point::new(1.0, 0.9, 0.8), 4.0)),
+        )
+    };
+    let camera = camera.with_lights(HittableList::new(Some(vec![Box::new(light())])));
+
+    let mut world = HittableList::new(None);
+    let checker =
+        CheckerTexture::from_colors(Point::new(0.9, 0.9, 0.9), Point::new(0.2, 0.3, 0.1), 8.0);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, -100.0, 0.0),
+        100.0,
+        Arc::new(Lambertian::with_texture(Arc::new(checker))),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(-0.6, 0.5, 0.0),
+        0.5,
+        Arc::new(Dielectric::new(1.5)),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(-0.6, 0.5, 0.0),
+        -0.4,
+        Arc::new(Dielectric::new(1.5)),
+    )));
+    let triangle = Triangle::new(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(0.0, 1.0, 0.0),
+        Arc::new(Metal::new(Point::new(0.8, 0.6, 0.2), 0.1)),
+    );
+    let placement = Mat4::translation(Vector::new(0.3, 0.0, -0.5)) * Mat4::rotation_y(20.0);
+    world.add(Box::new(
+        Transform::new(Box::new(triangle), placement).unwrap(),
+    ));
+    let glossy = Phong::new(Point::new(0.6, 0.2, 0.2), Point::new(0.3, 0.3, 0.3), 50.0);
+    let matte = Lambertian::new(Point::new(0.2, 0.2, 0.7));
+    world.add(Box::new(Sphere::new(
+        Point::new(0.7, 0.4, 0.3),
+        0.4,
+        Arc::new(Mix::new(Arc::new(glossy), Arc::new(matte), 0.3)),
+    )));
+    world.add(Box::new(light()));
+    world.sort_along_dominant_axis();
+
+    let path = std::env::temp_dir().join("raytracer_scene_test.json");
+    save(&camera, &world, &path).unwrap();
+    let (loaded_camera, loaded_world) = load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded_camera.dimensions(), (12, 8));
+    assert_eq!(loaded_world.len(), world.len());
+    assert_eq!(
+        loaded_camera.render_to_buffer(&loaded_world),
+        camera.render_to_buffer(&world)
+    );
+
+    // Scenes with something that can't be written out aren't saved at all.
+    let glowing = Animated::new(|t| DiffuseLight::new(Point::new(1.0, 1.0, 1.0), t));
+    world.add(Box::new(Sphere::new(
+        Point::default(),
+        1.0,
+        Arc::new(glowing),
+    )));
+    let error = to_json(&camera, &world).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn renders_every_scene_in_a_directory() {
+    use crate::point::Vector;
+
+    let dir = std::env::temp_dir().join("raytracer_render_all_test");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir(&dir).unwrap();
+    let camera = |vfov| {
+        let init = CameraInit {
+            vfov,
+            lookat: Point::new(0.0, 0.0, -1.0),
+            vup: Vector::new(0.0, 1.0, 0.0),
+            focus_dist: 1.0,
+            samples_per_pixel: 2,
+            quiet: true,
+            ..Default::default()
+        };
+        Camera::with_size(6, 4, init)
+    };
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.2, 0.1))),
+    )));
+    save(&camera(90.0), &world, dir.join("near.json")).unwrap();
+    save(&camera(30.0), &world, dir.join("far.json")).unwrap();
+    fs::write(dir.join("broken.json"), "{").unwrap();
+    fs::write(dir.join("notes.txt"), "not a scene").unwrap();
+
+    let failures = render_all(&dir, &dir).unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].0, dir.join("broken.json"));
+    for name in ["near.png", "far.png"] {
+        let image = image::open(dir.join(name)).unwrap();
+        assert_eq!((image.width(), image.height()), (6, 4));
+    }
+    assert!(!dir.join("notes.png").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use crate::{
+    brushed_metal::BrushedMetal,
+    camera::{Background, Camera, CameraInit},
+    dielectric::Dielectric,
+    diffuse_light::DiffuseLight,
+    hittable::{Hittable, HittableList},
+    lambertian::Lambertian,
+    material::Material,
+    matrix::Mat4,
+    metal::Metal,
+    point::{Point, Vector},
+    quad::{make_box, Quad},
+    shadow_catcher::ShadowCatcher,
+    sphere::Sphere,
+    transform::Transform,
+    utils::{random_between, seed_random},
+};
+
+/// The book's final scene of small random spheres around three large ones.
+/// Seeds the thread's random generator with `seed` first, so the same seed
+/// always builds the same scene (and leaves the generator in the same state
+/// for whatever rendering follows).
+pub fn random_spheres(seed: u64) -> HittableList {
+    seed_random(seed);
+    let mut world = HittableList::new(None);
+
+    for a in -11..11 {
+        for b in -11..11 {
+            let choose_mat = random_between(0.0, 1.0);
+            let center = Point::new(
+                f64::from(a) + 0.9 * random_between(0.0, 1.0),
+                0.2,
+                f64::from(b) + 0.9 * random_between(0.0, 1.0),
+            );
+
+            if (center - Point::new(4.0, 0.2, 0.0)).len() > 0.9 {
+                if choose_mat < 0.8 {
+                    let albedo = Point::random() * Point::random();
+                    let material = Arc::new(Lambertian::new(albedo));
+                    world.add(Box::new(Sphere::new(center, 0.2, material)));
+                } else if choose_mat < 0.95 {
+                    let albedo = Point::random();
+                    let fuzz = random_between(0.0, 0.5);
+                    let material = Arc::new(Metal::new(albedo, fuzz));
+                    world.add(Box::new(Sphere::new(center, 0.2, material)));
+                } else {
+                    let material = Arc::new(Dielectric::new(1.5));
+                    world.add(Box::new(Sphere::new(center, 0.2, material)));
+                }
+            }
+        }
+    }
+
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, -1000.0, -1.0),
+        1000.0,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+
+    world.add(Box::new(Sphere::new(
+        Point::new(-4.0, 1.0, 0.0),
+        1.0,
+        Arc::new(Lambertian::new(Point::new(0.4, 0.2, 0.1))),
+    )));
+
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 1.0, 0.0),
+        1.0,
+        Arc::new(Dielectric::new(1.5)),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(4.0, 1.0, 0.0),
+        1.0,
+        Arc::new(Metal::new(Point::new(0.7, 0.6, 0.5), 0.0)),
+    )));
+
+    world
+}
+
+pub fn brushed_metal() -> (Camera, HittableList) {
+    let camera_init = CameraInit {
+        vfov: 25.0,
+        lookfrom: Point::new(0.0, 1.5, 8.0),
+        lookat: Point::new(0.0, 1.0, 0.0),
+        vup: Vector::new(0.0, 1.0, 0.0),
+        focus_dist: 8.0,
+        defocus_angle: 0.0,
+        samples_per_pixel: 200,
+        ..Default::default()
+    };
+    let camera = Camera::new(16.0 / 9.0, 720, camera_init);
+    let mut world = HittableList::new(None);
+
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+
+    let color = Point::new(0.8, 0.8, 0.85);
+    world.add(Box::new(Sphere::new(
+        Point::new(-1.1, 1.0, 0.0),
+        1.0,
+        Arc::new(Metal::new(color, 0.2)),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(1.1, 1.0, 0.0),
+        1.0,
+        Arc::new(BrushedMetal::new(
+            color,
+            0.02,
+            0.4,
+            Vector::new(0.0, 1.0, 0.0),
+        )),
+    )));
+
+    (camera, world)
+}
+
+/// The three large spheres of `random_spheres` on a `ShadowCatcher` floor,
+/// rendered with a transparent background to lay over a photo of the floor.
+pub fn shadow_catcher() -> (Camera, HittableList) {
+    let camera_init = CameraInit {
+        vfov: 20.0,
+        lookfrom: Point::new(13.0, 2.0, 3.0),
+        lookat: Point::new(0.0, 0.0, 0.0),
+        vup: Vector::new(0.0, 1.0, 0.0),
+        focus_dist: 10.0,
+        samples_per_pixel: 200,
+        alpha_background: true,
+        ..Default::default()
+    };
+    let camera = Camera::new(16.0 / 9.0, 720, camera_init);
+    let mut world = HittableList::new(None);
+
+    world.add(Box::new(Quad::new(
+        Point::new(-20.0, 0.0, 20.0),
+        Vector::new(40.0, 0.0, 0.0),
+        Vector::new(0.0, 0.0, -40.0),
+        Arc::new(ShadowCatcher::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(-4.0, 1.0, 0.0),
+        1.0,
+        Arc::new(Lambertian::new(Point::new(0.4, 0.2, 0.1))),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 1.0, 0.0),
+        1.0,
+        Arc::new(Dielectric::new(1.5)),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(4.0, 1.0, 0.0),
+        1.0,
+        Arc::new(Metal::new(Point::new(0.7, 0.6, 0.5), 0.0)),
+    )));
+
+    (camera, world)
+}
+
+pub fn cornell_box() -> (Camera, HittableList) {
+    let camera = cornell_box_camera(600, 200).with_lights(cornell_box_lights());
+    (camera, cornell_box_world())
+}
+
+fn cornell_box_camera(image_width: u32, samples_per_pixel: u32) -> Camera {
+    let camera_init = CameraInit {
+        vfov: 40.0,
+        lookfrom: Point::new(278.0, 278.0, -800.0),
+        lookat: Point::new(278.0, 278.0, 0.0),
+        vup: Vector::new(0.0, 1.0, 0.0),
+        focus_dist: 10.0,
+        defocus_angle: 0.0,
+        samples_per_pixel,
+        background: Background::Solid(Point::default()),
+        ..Default::default()
+    };
+    Camera::new(1.0, image_width, camera_init)
+}
+
+fn cornell_box_light(mat: Arc<dyn Material>) -> Quad {
+    Quad::new(
+        Point::new(343.0, 554.0, 332.0),
+        Vector::new(-130.0, 0.0, 0.0),
+        Vector::new(0.0, 0.0, -105.0),
+        mat,
+    )
+}
+
+/// The ceiling light alone, to sample bounces toward.
+fn cornell_box_lights() -> HittableList {
+    let mat = Arc::new(DiffuseLight::new(Point::new(1.0, 1.0, 1.0), 15.0));
+    HittableList::new(Some(vec![Box::new(cornell_box_light(mat))]))
+}
+
+fn cornell_box_world() -> HittableList {
+    let red = Arc::new(Lambertian::new(Point::new(0.65, 0.05, 0.05)));
+    let white: Arc<dyn Material> = Arc::new(Lambertian::new(Point::new(0.73, 0.73, 0.73)));
+    let green = Arc::new(Lambertian::new(Point::new(0.12, 0.45, 0.15)));
+    let light = Arc::new(DiffuseLight::new(Point::new(1.0, 1.0, 1.0), 15.0));
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Quad::new(
+        Point::new(555.0, 0.0, 0.0),
+        Vector::new(0.0, 555.0, 0.0),
+        Vector::new(0.0, 0.0, 555.0),
+        green,
+    )));
+    world.add(Box::new(Quad::new(
+        Point::new(0.0, 0.0, 0.0),
+        Vector::new(0.0, 555.0, 0.0),
+        Vector::new(0.0, 0.0, 555.0),
+        red,
+    )));
+    world.add(Box::new(cornell_box_light(light)));
+    world.add(Box::new(Quad::new(
+        Point::new(0.0, 0.0, 0.0),
+        Vector::new(555.0, 0.0, 0.0),
+        Vector::new(0.0, 0.0, 555.0),
+        white.clone(),
+    )));
+    world.add(Box::new(Quad::new(
+        Point::new(555.0, 555.0, 555.0),
+        Vector::new(-555.0, 0.0, 0.0),
+        Vector::new(0.0, 0.0, -555.0),
+        white.clone(),
+    )));
+    world.add(Box::new(Quad::new(
+        Point::new(0.0, 0.0, 555.0),
+        Vector::new(555.0, 0.0, 0.0),
+        Vector::new(0.0, 555.0, 0.0),
+        white.clone(),
+    )));
+
+    let boxes = [
+        (
+            Point::new(165.0, 330.0, 165.0),
+            15.0,
+            Vector::new(265.0, 0.0, 295.0),
+        ),
+        (
+            Point::new(165.0, 165.0, 165.0),
+            -18.0,
+            Vector::new(130.0, 0.0, 65.0),
+        ),
+    ];
+    for (size, angle, offset) in boxes {
+        let sides: HittableList = make_box(Point::default(), size, white.clone())
+            .into_iter()
+            .map(|side| Box::new(side) as Box<dyn Hittable>)
+            .collect();
+        let placement = Mat4::translation(offset) * Mat4::rotation_y(angle);
+        if let Some(placed) = Transform::new(Box::new(sides), placement) {
+            world.add(Box::new(placed));
+        }
+    }
+
+    world
+}
+
+#[test]
+fn cornell_box_light_is_brighter_than_walls() {
+    let camera = cornell_box_camera(40, 8);
+    let (image, _) = camera.render_with_progress(&cornell_box_world(), Some(Box::new(|_| {})));
+    let luminance = |x: u32, y: u32| {
+        let [r, g, b] = image.get_pixel(x, y).0;
+        u32::from(r) + u32::from(g) + u32::from(b)
+    };
+
+    let light = luminance(20, 6);
+    assert_eq!(light, 3 * 255);
+    for (x, y) in [(1, 20), (38, 20), (20, 38), (10, 10)] {
+        assert!(
+            light > luminance(x, y),
+            "pixel ({}, {}) is as bright as the light",
+            x,
+            y
+        );
+    }
+}
+
+#[test]
+fn random_spheres_is_deterministic_for_a_seed() {
+    use crate::{hittable::Interval, ray::Ray};
+
+    let first = random_spheres(42);
+    let second = random_spheres(42);
+    assert_eq!(first.len(), second.len());
+    assert!(first.len() > 4);
+
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    for x in -10..10 {
+        let ray = Ray::new(
+            Point::new(13.0, 2.0, 3.0),
+            Vector::new(-13.0, -2.0, f64::from(x) * 0.3),
+        );
+        assert_eq!(
+            first.hit(&ray, &interval).map(|r| r.t),
+            second.hit(&ray, &interval).map(|r| r.t)
+        );
+    }
+}
+
+#[test]
+fn light_sampling_reduces_cornell_box_noise() {
+    let world = cornell_box_world();
+    let noise = |camera: Camera| {
+        let (image, _) = camera.render_with_progress(&world, Some(Box::new(|_| {})));
+        let luminance = |x: u32, y: u32| {
+            let [r, g, b] = image.get_pixel(x, y).0;
+            i32::from(r) + i32::from(g) + i32::from(b)
+        };
+        // Neighboring pixels on the left wall should all look alike.
+        (10..30)
+            .flat_map(|y| (1..5).map(move |x| (x, y)))
+            .map(|(x, y)| (luminance(x, y) - luminance(x + 1, y)).abs())
+            .sum::<i32>()
+    };
+
+    let without = noise(cornell_box_camera(40, 8));
+    let with = noise(cornell_box_camera(40, 8).with_lights(cornell_box_lights()));
+    assert!(
+        with * 2 < without,
+        "noise {} with lights, {} without",
+        with,
+        without
+    );
+}
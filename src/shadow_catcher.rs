@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    lambertian::Lambertian,
+    material::{Material, MaterialKind, ScatterRecord},
+    point::Point,
+    ray::Ray,
+    scene::Json,
+    texture::{SolidColor, Texture},
+};
+
+/// A ground for compositing renders onto a photo: with the camera's
+/// `alpha_background` set, the camera sees through it, transparent where it
+/// is lit and dark and partly opaque where it is in shadow, as much as it
+/// is shadowed. The shadow is of the camera's lights if it has any, else of
+/// the sky. Bounces see it as a diffuse surface of `albedo`, which should
+/// match the photo's ground, so it still lights and shows in the objects
+/// above it. Without `alpha_background` it is just that diffuse surface.
+pub struct ShadowCatcher {
+    albedo: Arc<dyn Texture>,
+    diffuse: Lambertian,
+}
+
+impl ShadowCatcher {
+    pub fn new(albedo: Point) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn with_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self {
+            diffuse: Lambertian::with_texture(albedo.clone()),
+            albedo,
+        }
+    }
+}
+
+impl Material for ShadowCatcher {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::ShadowCatcher
+    }
+
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
+        self.diffuse.scatter(r_in, rec)
+    }
+
+    fn scatter_record(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        self.diffuse.scatter_record(r_in, rec)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        self.diffuse.scattering_pdf(r_in, rec, scattered)
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "shadow_catcher",
+            [("albedo", self.albedo.to_json()?)],
+        ))
+    }
+}
+
+#[test]
+fn only_shadows_show_on_the_catcher() {
+    use crate::{
+        camera::{test_init, Camera, CameraInit},
+        hittable::HittableList,
+        quad::Quad,
+        sphere::Sphere,
+    };
+
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Quad::new(
+        Point::new(-50.0, -0.5, 50.0),
+        Point::new(100.0, 0.0, 0.0),
+        Point::new(0.0, 0.0, -100.0),
+        Arc::new(ShadowCatcher::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -1.0),
+        0.5,
+        Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+    )));
+    let init = CameraInit {
+        samples_per_pixel: 16,
+        alpha_background: true,
+        ..test_init()
+    };
+    let (image, _) = Camera::new(1.0, 32, init).render_rgba(&world);
+    let alpha = |x, y| image.get_pixel(x, y)[3];
+    // The sphere is opaque, the sky clear, and the catcher clear far from
+    // the sphere but dark in the contact shadow right under it.
+    assert_eq!(alpha(16, 16), 255);
+    assert_eq!(alpha(16, 2), 0);
+    assert!(alpha(1, 24) < 64, "open floor {}", alpha(1, 24));
+    assert!(alpha(16, 26) > 128, "under the sphere {}", alpha(16, 26));
+    assert_eq!(image.get_pixel(16, 26).0[..3], [0, 0, 0]);
+}
@@ -0,0 +1,184 @@
+//! SIMD-backed arithmetic for `Point`, enabled by the `simd` feature.
+//!
+//! Coordinates are packed into the first three lanes of a 4-wide `f64`
+//! register with the last lane held at zero. On x86_64 builds with AVX
+//! enabled (e.g. `RUSTFLAGS="-C target-feature=+avx"`) this is a `__m256d`;
+//! elsewhere it falls back to an aligned array the compiler can vectorize.
+//! Results are identical to the scalar implementation.
+
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::point::Point;
+
+#[cfg(all(target_arch = "x86_64", target_feature = "avx"))]
+mod lanes {
+    use std::arch::x86_64::*;
+
+    // SAFETY (for every intrinsic below): this module is only compiled when
+    // AVX is enabled for the whole build, so the instructions are available.
+    #[derive(Clone, Copy)]
+    pub struct F64x4(__m256d);
+
+    impl F64x4 {
+        pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+            unsafe { Self(_mm256_set_pd(w, z, y, x)) }
+        }
+
+        pub fn splat(v: f64) -> Self {
+            unsafe { Self(_mm256_set1_pd(v)) }
+        }
+
+        pub fn add(self, rhs: Self) -> Self {
+            unsafe { Self(_mm256_add_pd(self.0, rhs.0)) }
+        }
+
+        pub fn sub(self, rhs: Self) -> Self {
+            unsafe { Self(_mm256_sub_pd(self.0, rhs.0)) }
+        }
+
+        pub fn mul(self, rhs: Self) -> Self {
+            unsafe { Self(_mm256_mul_pd(self.0, rhs.0)) }
+        }
+
+        pub fn to_array(self) -> [f64; 4] {
+            let mut out = [0.0; 4];
+            // `out` has room for the four lanes and the store is unaligned.
+            unsafe { _mm256_storeu_pd(out.as_mut_ptr(), self.0) };
+            out
+        }
+    }
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "avx")))]
+mod lanes {
+    #[derive(Clone, Copy)]
+    #[repr(align(32))]
+    pub struct F64x4([f64; 4]);
+
+    impl F64x4 {
+        pub fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+            Self([x, y, z, w])
+        }
+
+        pub fn splat(v: f64) -> Self {
+            Self([v; 4])
+        }
+
+        fn zip(self, rhs: Self, f: impl Fn(f64, f64) -> f64) -> Self {
+            let (a, b) = (self.0, rhs.0);
+            Self([f(a[0], b[0]), f(a[1], b[1]), f(a[2], b[2]), f(a[3], b[3])])
+        }
+
+        pub fn add(self, rhs: Self) -> Self {
+            self.zip(rhs, |a, b| a + b)
+        }
+
+        pub fn sub(self, rhs: Self) -> Self {
+            self.zip(rhs, |a, b| a - b)
+        }
+
+        pub fn mul(self, rhs: Self) -> Self {
+            self.zip(rhs, |a, b| a * b)
+        }
+
+        pub fn to_array(self) -> [f64; 4] {
+            self.0
+        }
+    }
+}
+
+use lanes::F64x4;
+
+impl From<Point> for F64x4 {
+    fn from(p: Point) -> Self {
+        F64x4::new(p.x(), p.y(), p.z(), 0.0)
+    }
+}
+
+impl From<F64x4> for Point {
+    fn from(v: F64x4) -> Self {
+        let [x, y, z, _] = v.to_array();
+        Point::new(x, y, z)
+    }
+}
+
+pub fn dot(lhs: &Point, rhs: &Point) -> f64 {
+    let [x, y, z, w] = F64x4::from(*lhs).mul(F64x4::from(*rhs)).to_array();
+    x + y + z + w
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        F64x4::from(self).add(F64x4::from(rhs)).into()
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        F64x4::from(self).sub(F64x4::from(rhs)).into()
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        F64x4::splat(0.0).sub(F64x4::from(self)).into()
+    }
+}
+
+impl Mul<Point> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: Point) -> Self::Output {
+        F64x4::from(self).mul(F64x4::from(rhs)).into()
+    }
+}
+
+impl Mul<f64> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        F64x4::from(self).mul(F64x4::splat(rhs)).into()
+    }
+}
+
+impl Mul<Point> for f64 {
+    type Output = Point;
+
+    fn mul(self, rhs: Point) -> Self::Output {
+        F64x4::splat(self).mul(F64x4::from(rhs)).into()
+    }
+}
+
+#[test]
+fn matches_scalar_arithmetic() {
+    use crate::utils::random_between;
+
+    for _ in 0..1000 {
+        let a = Point::random_between(-10.0, 10.0);
+        let b = Point::random_between(-10.0, 10.0);
+        let s = random_between(-10.0, 10.0);
+
+        assert_eq!(
+            a + b,
+            Point::new(a.x() + b.x(), a.y() + b.y(), a.z() + b.z())
+        );
+        assert_eq!(
+            a - b,
+            Point::new(a.x() - b.x(), a.y() - b.y(), a.z() - b.z())
+        );
+        assert_eq!(
+            a * b,
+            Point::new(a.x() * b.x(), a.y() * b.y(), a.z() * b.z())
+        );
+        assert_eq!(a * s, Point::new(a.x() * s, a.y() * s, a.z() * s));
+        assert_eq!(s * a, Point::new(s * a.x(), s * a.y(), s * a.z()));
+        assert_eq!(-a, Point::new(-a.x(), -a.y(), -a.z()));
+        assert_eq!(dot(&a, &b), a.x() * b.x() + a.y() * b.y() + a.z() * b.z());
+    }
+}
@@ -1,32 +1,60 @@
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::f64::consts::PI;
+use std::sync::Arc;
 
+use crate::aabb::Aabb;
 use crate::hittable::{HitRecord, Hittable, Interval};
 use crate::material::Material;
-use crate::point::{dot, Point};
+use crate::onb::Onb;
+use crate::point::{Point, Vector};
 use crate::ray::Ray;
+use crate::scene::Json;
+use crate::utils::random_between;
 
 pub struct Sphere {
     center: Point,
+    /// How far the center moves from time 0 to time 1.
+    motion: Vector,
     radius: f64,
-    mat: Rc<RefCell<dyn Material>>,
+    mat: Arc<dyn Material>,
 }
 
 impl Sphere {
-    pub fn new(center: Point, radius: f64, mat: Rc<RefCell<dyn Material>>) -> Self {
+    /// A negative `radius` turns the sphere inside out: its surface
+    /// normals point toward the center, so it reads as a hollow in whatever
+    /// surrounds it, like the air bubble inside a glass sphere.
+    pub fn new(center: Point, radius: f64, mat: Arc<dyn Material>) -> Self {
+        Self::moving(center, center, radius, mat)
+    }
+
+    /// A sphere moving steadily from `from` at time 0 to `to` at time 1, and
+    /// holding still before and after, for motion blur. Sampled as a light,
+    /// it is where it is at time 0.
+    pub fn moving(from: Point, to: Point, radius: f64, mat: Arc<dyn Material>) -> Self {
         Self {
-            center,
+            center: from,
+            motion: to - from,
             radius,
             mat,
         }
     }
-}
 
-impl Hittable for Sphere {
-    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
-        let oc = r.origin() - self.center;
-        let a = r.direction().len_squared();
-        let half_b = dot(&oc, &r.direction());
+    fn center_at(&self, time: f64) -> Point {
+        self.center + time.clamp(0.0, 1.0) * self.motion
+    }
+
+    /// Cosine of the half-angle of the cone the sphere fills as seen from
+    /// `origin`, or `None` from inside it.
+    fn cos_theta_max(&self, origin: &Point) -> Option<f64> {
+        let distance_squared = (self.center - *origin).len_squared();
+        let ratio = self.radius * self.radius / distance_squared;
+        (ratio < 1.0).then(|| (1.0 - ratio).sqrt())
+    }
+
+    /// Both `t` at which `r` crosses the sphere, nearest first.
+    fn roots(&self, r: &Ray) -> Option<[f64; 2]> {
+        let oc = r.origin() - self.center_at(r.time());
+        let a = r.direction_len_squared();
+        let half_b = oc.dot(&r.direction());
         let c = oc.len_squared() - self.radius * self.radius;
 
         let discriminant = half_b * half_b - a * c;
@@ -35,25 +63,433 @@ impl Hittable for Sphere {
         }
 
         let sqrtd = discriminant.sqrt();
-        let mut root = (-half_b - sqrtd) / a;
-        if !ray_t.surrounds(root) {
-            root = (-half_b + sqrtd) / a;
-            if !ray_t.surrounds(root) {
-                return None;
-            }
-        }
+        Some([(-half_b - sqrtd) / a, (-half_b + sqrtd) / a])
+    }
+
+    fn root(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.roots(r)?.into_iter().find(|t| ray_t.surrounds(*t))
+    }
 
-        let t = root;
-        let p = r.at(root);
-        let normal = ((p - self.center) / self.radius)?;
-        let front_face = dot(&r.direction(), &normal) < 0.0;
+    fn surface_at(&self, r: &Ray, t: f64) -> Option<(Point, Vector, bool)> {
+        let p = r.at(t);
+        let normal = ((p - self.center_at(r.time())) / self.radius)?;
+        let front_face = r.direction().dot(&normal) < 0.0;
         let normal = if front_face { normal } else { -normal };
+        Some((p, normal, front_face))
+    }
+
+    /// The directions `u` and `v` grow in at `p` on the sphere as it is at
+    /// `time`, with any tangent at the poles where `u` has none.
+    fn tangents(&self, p: &Point, time: f64) -> (Vector, Vector) {
+        let n = ((*p - self.center_at(time)) / self.radius.abs()).unwrap_or_default();
+        let tangent = Vector::new(n.z(), 0.0, -n.x()).unit().unwrap_or_else(|| {
+            n.cross(&Vector::new(1.0, 0.0, 0.0))
+                .unit()
+                .unwrap_or_default()
+        });
+        (tangent, n.cross(&tangent))
+    }
+
+    /// Longitude and latitude of `p` on the sphere as it is at `time`, as
+    /// `u` from -x around through -z and `v` from the bottom pole up.
+    fn uv(&self, p: &Point, time: f64) -> (f64, f64) {
+        let n = ((*p - self.center_at(time)) / self.radius.abs()).unwrap_or_default();
+        let theta = (-n.y()).clamp(-1.0, 1.0).acos();
+        let phi = (-n.z()).atan2(n.x()) + PI;
+        (phi / (2.0 * PI), theta / PI)
+    }
+}
+
+impl Hittable for Sphere {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let t = self.root(r, ray_t)?;
+        let (p, normal, front_face) = self.surface_at(r, t)?;
+        let (u, v) = self.uv(&p, r.time());
+        let (tangent, bitangent) = self.tangents(&p, r.time());
         Some(HitRecord {
             t,
+            u,
+            v,
             p,
             normal,
             front_face,
+            tangent: Some(tangent),
+            bitangent: Some(bitangent),
+            id: None,
             mat: self.mat.clone(),
         })
     }
+
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.root(r, ray_t)
+    }
+
+    fn hit_into(&self, r: &Ray, ray_t: &Interval, out: &mut HitRecord) -> bool {
+        let Some(t) = self.root(r, ray_t) else {
+            return false;
+        };
+        let Some((p, normal, front_face)) = self.surface_at(r, t) else {
+            return false;
+        };
+        (out.u, out.v) = self.uv(&p, r.time());
+        let (tangent, bitangent) = self.tangents(&p, r.time());
+        (out.tangent, out.bitangent) = (Some(tangent), Some(bitangent));
+        out.id = None;
+        out.t = t;
+        out.p = p;
+        out.normal = normal;
+        out.front_face = front_face;
+        if !Arc::ptr_eq(&out.mat, &self.mat) {
+            out.mat = self.mat.clone();
+        }
+        true
+    }
+
+    /// Around the whole of a moving sphere's path.
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        Some((
+            self.center + 0.5 * self.motion,
+            self.radius.abs() + 0.5 * self.motion.len(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let start = Aabb::around_sphere(self.center, self.radius);
+        Some(start.union(&Aabb::around_sphere(self.center + self.motion, self.radius)))
+    }
+
+    /// Uniform over the cone of directions from `origin` that hit the
+    /// sphere.
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        let ray = Ray::new(*origin, *direction);
+        if self
+            .hit_distance(&ray, &Interval::new_set_interval(0.001, f64::MAX))
+            .is_none()
+        {
+            return 0.0;
+        }
+        let Some(cos_theta_max) = self.cos_theta_max(origin) else {
+            return 0.0;
+        };
+        1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        let Some(cos_theta_max) = self.cos_theta_max(origin) else {
+            return Point::random_in_unit_vector().unwrap_or(Vector::new(1.0, 0.0, 0.0));
+        };
+        let phi = 2.0 * PI * random_between(0.0, 1.0);
+        let z = 1.0 + random_between(0.0, 1.0) * (cos_theta_max - 1.0);
+        let sin_theta = (1.0 - z * z).sqrt();
+        Onb::from_w(&(self.center - *origin)).local(Point::new(
+            phi.cos() * sin_theta,
+            phi.sin() * sin_theta,
+            z,
+        ))
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        let mut json = Json::tagged(
+            "sphere",
+            [
+                ("center", self.center.into()),
+                ("radius", self.radius.into()),
+                ("material", self.mat.to_json()?),
+            ],
+        );
+        if let (Json::Object(fields), false) = (&mut json, self.motion == Vector::default()) {
+            fields.push(("to".to_string(), (self.center + self.motion).into()));
+        }
+        Some(json)
+    }
+}
+
+/// The part of a sphere within a range of polar angle, from 0 straight up
+/// to 180 straight down, and of azimuth, from 0 to 360 the way `u` runs
+/// around a `Sphere`, all in degrees: a dome, a lens or a slice. It is open
+/// where it is cut, showing its inside.
+pub struct SphereCap {
+    sphere: Sphere,
+    polar: (f64, f64),
+    azimuth: (f64, f64),
+}
+
+impl SphereCap {
+    /// Every azimuth between polar angles `polar.0` and `polar.1`, so `(0.0,
+    /// 90.0)` is the top half.
+    pub fn new(center: Point, radius: f64, polar: (f64, f64), mat: Arc<dyn Material>) -> Self {
+        Self {
+            sphere: Sphere::new(center, radius, mat),
+            polar,
+            azimuth: (0.0, 360.0),
+        }
+    }
+
+    /// Keeps only azimuths between `azimuth.0` and `azimuth.1`.
+    pub fn with_azimuth(mut self, azimuth: (f64, f64)) -> Self {
+        self.azimuth = azimuth;
+        self
+    }
+
+    fn contains(&self, p: &Point) -> bool {
+        let (u, v) = self.sphere.uv(p, 0.0);
+        let (polar, azimuth) = (180.0 * (1.0 - v), 360.0 * u);
+        (self.polar.0..=self.polar.1).contains(&polar)
+            && (self.azimuth.0..=self.azimuth.1).contains(&azimuth)
+    }
+
+    fn root(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.sphere
+            .roots(r)?
+            .into_iter()
+            .find(|t| ray_t.surrounds(*t) && self.contains(&r.at(*t)))
+    }
+}
+
+impl Hittable for SphereCap {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let t = self.root(r, ray_t)?;
+        let (p, normal, front_face) = self.sphere.surface_at(r, t)?;
+        let (u, v) = self.sphere.uv(&p, 0.0);
+        let (tangent, bitangent) = self.sphere.tangents(&p, 0.0);
+        Some(HitRecord {
+            t,
+            u,
+            v,
+            p,
+            normal,
+            front_face,
+            tangent: Some(tangent),
+            bitangent: Some(bitangent),
+            id: None,
+            mat: self.sphere.mat.clone(),
+        })
+    }
+
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.root(r, ray_t)
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        self.sphere.bounding_sphere()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.sphere.bounding_box()
+    }
+
+    /// Sampled as the whole sphere, so directions toward the cut away part
+    /// are drawn too but find nothing there.
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        self.sphere.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        self.sphere.random(origin)
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "sphere_cap",
+            [
+                ("center", self.sphere.center.into()),
+                ("radius", self.sphere.radius.into()),
+                ("polar_min", self.polar.0.into()),
+                ("polar_max", self.polar.1.into()),
+                ("azimuth_min", self.azimuth.0.into()),
+                ("azimuth_max", self.azimuth.1.into()),
+                ("material", self.sphere.mat.to_json()?),
+            ],
+        ))
+    }
+}
+
+#[test]
+fn uv_wraps_around_the_equator() {
+    use crate::lambertian::Lambertian;
+
+    let sphere = Sphere::new(
+        Point::default(),
+        2.0,
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let uv = |x, y, z| sphere.uv(&Point::new(x, y, z), 0.0);
+    assert_eq!(uv(-2.0, 0.0, 0.0), (0.0, 0.5));
+    assert_eq!(uv(0.0, 0.0, 2.0), (0.25, 0.5));
+    assert_eq!(uv(2.0, 0.0, 0.0), (0.5, 0.5));
+    assert_eq!(uv(0.0, 2.0, 0.0).1, 1.0);
+    assert_eq!(uv(0.0, -2.0, 0.0).1, 0.0);
+}
+
+#[test]
+fn pdf_integrates_to_one_over_the_sphere_of_directions() {
+    use crate::lambertian::Lambertian;
+
+    let sphere = Sphere::new(
+        Point::new(0.0, 0.0, -3.0),
+        1.0,
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let origin = Point::default();
+    let samples = 200_000;
+    let integral: f64 = (0..samples)
+        .map(|_| sphere.pdf_value(&origin, &Point::random_in_unit_vector().unwrap()))
+        .sum::<f64>()
+        * 4.0
+        * PI
+        / samples as f64;
+    assert!((integral - 1.0).abs() < 0.05, "integral {}", integral);
+
+    for _ in 0..100 {
+        let direction = sphere.random(&origin);
+        assert!(sphere.pdf_value(&origin, &direction) > 0.0);
+    }
+}
+
+#[test]
+fn negative_radius_makes_a_bubble_inside_glass() {
+    use crate::{
+        camera::{test_init, Camera, CameraInit},
+        dielectric::Dielectric,
+        hittable::HittableList,
+    };
+
+    let glass = Arc::new(Dielectric::new(1.5));
+    let bubble = Sphere::new(Point::new(0.0, 0.0, -1.0), -0.4, glass.clone());
+    assert_eq!(
+        bubble.bounding_sphere(),
+        Some((Point::new(0.0, 0.0, -1.0), 0.4))
+    );
+    assert_eq!(bubble.uv(&Point::new(0.0, 0.4, -1.0), 0.0).1, 1.0);
+
+    // Leaving the glass into the bubble, the hit is on the back face, with
+    // the normal turned back against the ray as for any back face.
+    let ray = Ray::new(Point::default(), Vector::new(0.0, 0.0, -1.0));
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let rec = bubble.hit(&ray, &interval).unwrap();
+    assert!((rec.t - 0.6).abs() < 1e-12);
+    assert!(!rec.front_face);
+    assert_eq!(rec.normal, Vector::new(0.0, 0.0, 1.0));
+
+    let render = |sorted: bool| {
+        let mut world = HittableList::new(None);
+        world.add(Box::new(Sphere::new(
+            Point::new(0.0, 0.0, -1.0),
+            0.5,
+            glass.clone(),
+        )));
+        world.add(Box::new(Sphere::new(
+            Point::new(0.0, 0.0, -1.0),
+            -0.4,
+            glass.clone(),
+        )));
+        if sorted {
+            world.sort_along_dominant_axis();
+        }
+        let init = CameraInit {
+            samples_per_pixel: 4,
+            ..test_init()
+        };
+        Camera::new(1.0, 16, init).render_to_buffer(&world)
+    };
+    // Sorting relies on the bounding spheres, which must not be inverted.
+    assert_eq!(render(true), render(false));
+}
+
+#[test]
+fn moving_spheres_are_hit_along_their_path() {
+    use crate::lambertian::Lambertian;
+
+    let sphere = Sphere::moving(
+        Point::new(0.0, 0.0, -2.0),
+        Point::new(2.0, 0.0, -2.0),
+        0.5,
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let hit = |x: f64, time: f64| {
+        let r = Ray::new(Point::new(x, 0.0, 0.0), Vector::new(0.0, 0.0, -1.0)).with_time(time);
+        sphere.hit(&r, &interval).is_some()
+    };
+    assert!(hit(0.0, 0.0) && !hit(2.0, 0.0));
+    assert!(hit(1.0, 0.5) && !hit(0.0, 0.5) && !hit(2.0, 0.5));
+    assert!(hit(2.0, 1.0) && !hit(0.0, 1.0));
+    // It stays put past the end of its path.
+    assert!(hit(2.0, 3.0) && hit(0.0, -1.0));
+
+    let bounds = sphere.bounding_box().unwrap();
+    assert_eq!(bounds.min(), Point::new(-0.5, -0.5, -2.5));
+    assert_eq!(bounds.max(), Point::new(2.5, 0.5, -1.5));
+    assert_eq!(
+        sphere.bounding_sphere(),
+        Some((Point::new(1.0, 0.0, -2.0), 1.5))
+    );
+}
+
+#[test]
+fn hemisphere_cap_is_open_below_its_cut() {
+    use crate::lambertian::Lambertian;
+
+    let dome = SphereCap::new(
+        Point::default(),
+        1.0,
+        (0.0, 90.0),
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let hit = |origin, direction| dome.hit(&Ray::new(origin, direction), &interval);
+
+    let down = Vector::new(0.0, -1.0, 0.0);
+    let top = hit(Point::new(0.0, 5.0, 0.0), down).unwrap();
+    assert!((top.t - 4.0).abs() < 1e-12 && top.front_face);
+    assert_eq!(top.normal, Vector::new(0.0, 1.0, 0.0));
+
+    // From below, the ray passes the missing bottom to the dome's inside.
+    let inside = hit(Point::new(0.0, -5.0, 0.0), -down).unwrap();
+    assert!((inside.t - 6.0).abs() < 1e-12 && !inside.front_face);
+
+    let across = Vector::new(-1.0, 0.0, 0.0);
+    assert!(hit(Point::new(5.0, 0.5, 0.0), across).is_some());
+    assert!(hit(Point::new(5.0, -0.5, 0.0), across).is_none());
+
+    // Half of the dome, on the +z side where u is under a half.
+    let half = SphereCap::new(
+        Point::default(),
+        1.0,
+        (0.0, 90.0),
+        Arc::new(Lambertian::new(Point::default())),
+    )
+    .with_azimuth((0.0, 180.0));
+    let toward = |z: f64| Ray::new(Point::new(0.0, 0.5, 5.0 * z), Vector::new(0.0, 0.0, -z));
+    assert!(half.hit(&toward(1.0), &interval).unwrap().front_face);
+    assert!(!half.hit(&toward(-1.0), &interval).unwrap().front_face);
+}
+
+#[test]
+fn tangent_frame_lies_on_the_surface() {
+    use crate::lambertian::Lambertian;
+
+    let sphere = Sphere::new(
+        Point::new(1.0, 2.0, -3.0),
+        1.5,
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    for direction in [
+        Vector::new(0.0, 0.0, -1.0),
+        Vector::new(0.1, 0.3, -1.0),
+        Vector::new(-1.0, -0.5, 0.2),
+    ] {
+        let origin = Point::new(1.0, 2.0, -3.0) - 4.0 * direction.unit().unwrap();
+        let rec = sphere.hit(&Ray::new(origin, direction), &interval).unwrap();
+        let (tangent, bitangent) = (rec.tangent.unwrap(), rec.bitangent.unwrap());
+        assert!(rec.normal.dot(&tangent).abs() < 1e-12);
+        assert!(rec.normal.dot(&bitangent).abs() < 1e-12);
+        assert!(tangent.dot(&bitangent).abs() < 1e-12);
+        assert!((tangent.len() - 1.0).abs() < 1e-12);
+
+        // Stepping along the tangent grows `u`.
+        let step = sphere.uv(&(rec.p + 1e-6 * tangent), 0.0);
+        assert!(step.0 > rec.u || rec.u > 0.99, "{:?}", (rec.u, step));
+    }
 }
@@ -0,0 +1,111 @@
+use std::{
+    cell::Cell,
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+thread_local! {
+    static INTERSECTION_TESTS: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Counts one intersection test, of a bounding box or an object, on the
+/// current thread.
+pub fn record_intersection_test() {
+    INTERSECTION_TESTS.with(|x| x.set(x.get() + 1));
+}
+
+/// Intersection tests run on the current thread so far. Only differences
+/// are meaningful, such as the tests one ray took.
+pub fn intersection_tests() -> u64 {
+    INTERSECTION_TESTS.with(Cell::get)
+}
+
+/// Counters collected while tracing. Updates use relaxed atomics, which are
+/// cheap enough to leave on for every ray.
+#[derive(Default, Debug)]
+pub struct RenderStats {
+    primary_rays: AtomicU64,
+    scattered_rays: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl RenderStats {
+    pub fn record_primary(&self) {
+        self.primary_rays.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_scattered(&self) {
+        self.scattered_rays.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn primary_rays(&self) -> u64 {
+        self.primary_rays.load(Ordering::Relaxed)
+    }
+
+    pub fn scattered_rays(&self) -> u64 {
+        self.scattered_rays.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    pub fn total_rays(&self) -> u64 {
+        self.primary_rays() + self.scattered_rays()
+    }
+
+    /// Scattered rays per primary ray.
+    pub fn average_bounces(&self) -> f64 {
+        match self.primary_rays() {
+            0 => 0.0,
+            primary => self.scattered_rays() as f64 / primary as f64,
+        }
+    }
+
+    /// Fraction of traced rays that hit something.
+    pub fn hit_ratio(&self) -> f64 {
+        match self.total_rays() {
+            0 => 0.0,
+            total => (total - self.misses()) as f64 / total as f64,
+        }
+    }
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rays ({} primary, {} scattered), {} misses, {:.1}% hits, {:.2} bounces per primary ray",
+            self.total_rays(),
+            self.primary_rays(),
+            self.scattered_rays(),
+            self.misses(),
+            self.hit_ratio() * 100.0,
+            self.average_bounces()
+        )
+    }
+}
+
+#[test]
+fn derives_ratios_from_counts() {
+    let stats = RenderStats::default();
+    assert_eq!(stats.hit_ratio(), 0.0);
+    assert_eq!(stats.average_bounces(), 0.0);
+
+    for _ in 0..2 {
+        stats.record_primary();
+    }
+    for _ in 0..6 {
+        stats.record_scattered();
+    }
+    stats.record_miss();
+    stats.record_miss();
+
+    assert_eq!(stats.total_rays(), 8);
+    assert_eq!(stats.average_bounces(), 3.0);
+    assert_eq!(stats.hit_ratio(), 0.75);
+}
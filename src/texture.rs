@@ -0,0 +1,214 @@
+use std::{path::Path, sync::Arc};
+
+use image::{ImageResult, RgbImage};
+
+use crate::{point::Point, scene::Json};
+
+/// A color that varies over a surface, looked up by the surface
+/// coordinates `(u, v)` or the hit point `p`.
+pub trait Texture: Send + Sync {
+    fn value(&self, u: f64, v: f64, p: &Point) -> Point;
+
+    /// This texture as it's written to a scene file, or `None` if it can't
+    /// be.
+    fn to_json(&self) -> Option<Json> {
+        None
+    }
+}
+
+pub struct SolidColor {
+    color: Point,
+}
+
+impl SolidColor {
+    pub fn new(color: Point) -> Self {
+        Self { color }
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _p: &Point) -> Point {
+        self.color
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged("solid", [("color", self.color.into())]))
+    }
+}
+
+/// Alternates between two textures in a grid of `scale` by `scale` checks
+/// over the unit square of surface coordinates.
+pub struct CheckerTexture {
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+    scale: f64,
+}
+
+impl CheckerTexture {
+    pub fn new(even: Arc<dyn Texture>, odd: Arc<dyn Texture>, scale: f64) -> Self {
+        Self { even, odd, scale }
+    }
+
+    pub fn from_colors(even: Point, odd: Point, scale: f64) -> Self {
+        Self::new(
+            Arc::new(SolidColor::new(even)),
+            Arc::new(SolidColor::new(odd)),
+            scale,
+        )
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f64, v: f64, p: &Point) -> Point {
+        let checks = (u * self.scale).floor() + (v * self.scale).floor();
+        if checks.rem_euclid(2.0) == 0.0 {
+            self.even.value(u, v, p)
+        } else {
+            self.odd.value(u, v, p)
+        }
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "checker",
+            [
+                ("even", self.even.to_json()?),
+                ("odd", self.odd.to_json()?),
+                ("scale", self.scale.into()),
+            ],
+        ))
+    }
+}
+
+/// How texture coordinates outside the unit square map back into it.
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+pub enum WrapMode {
+    /// Tiles the texture.
+    #[default]
+    Repeat,
+    /// Extends the edge texels.
+    Clamp,
+    /// Tiles the texture, flipping every other copy.
+    Mirror,
+}
+
+impl WrapMode {
+    /// Maps texel index `i` into `0..n`.
+    pub fn index(&self, i: i64, n: i64) -> i64 {
+        match self {
+            Self::Repeat => i.rem_euclid(n),
+            Self::Clamp => i.clamp(0, n - 1),
+            Self::Mirror => {
+                let i = i.rem_euclid(2 * n);
+                if i < n {
+                    i
+                } else {
+                    2 * n - 1 - i
+                }
+            }
+        }
+    }
+}
+
+/// An image mapped onto the unit square of `(u, v)`, with `v` going up from
+/// the bottom row.
+#[derive(Debug, PartialEq)]
+pub struct ImageTexture {
+    image: RgbImage,
+    bilinear: bool,
+    wrap: WrapMode,
+}
+
+impl ImageTexture {
+    /// Samples the nearest texel, or blends the four around `(u, v)` if
+    /// `bilinear` is set.
+    pub fn new(image: RgbImage, bilinear: bool) -> Self {
+        Self {
+            image,
+            bilinear,
+            wrap: WrapMode::default(),
+        }
+    }
+
+    pub fn with_wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn load(path: impl AsRef<Path>, bilinear: bool) -> ImageResult<Self> {
+        Ok(Self::new(image::open(path)?.to_rgb8(), bilinear))
+    }
+
+    /// The texel at column `i` and row `j`, wrapped into the image, converted
+    /// back from the gamma the camera encodes with.
+    fn texel(&self, i: i64, j: i64) -> Point {
+        let i = self.wrap.index(i, i64::from(self.image.width())) as u32;
+        let j = self.wrap.index(j, i64::from(self.image.height())) as u32;
+        let [r, g, b] = self.image.get_pixel(i, j).0;
+        let decode = |c: u8| {
+            let c = f64::from(c) / 255.0;
+            c * c
+        };
+        Point::new(decode(r), decode(g), decode(b))
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: &Point) -> Point {
+        if self.image.width() == 0 || self.image.height() == 0 {
+            return Point::new(0.0, 1.0, 1.0);
+        }
+
+        let x = u * f64::from(self.image.width());
+        let y = (1.0 - v) * f64::from(self.image.height());
+        if !self.bilinear {
+            return self.texel(x.floor() as i64, y.floor() as i64);
+        }
+
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (i, j) = (x.floor(), y.floor());
+        let (fx, fy) = (x - i, y - j);
+        let (i, j) = (i as i64, j as i64);
+        let top = (1.0 - fx) * self.texel(i, j) + fx * self.texel(i + 1, j);
+        let bottom = (1.0 - fx) * self.texel(i, j + 1) + fx * self.texel(i + 1, j + 1);
+        (1.0 - fy) * top + fy * bottom
+    }
+}
+
+#[test]
+fn bilinear_blends_neighboring_texels() {
+    let mut image = RgbImage::new(2, 1);
+    image.put_pixel(0, 0, image::Rgb([0, 0, 0]));
+    image.put_pixel(1, 0, image::Rgb([255, 255, 255]));
+    let texture = ImageTexture::new(image, true).with_wrap(WrapMode::Clamp);
+    let p = Point::default();
+
+    assert_eq!(texture.value(0.25, 0.5, &p), Point::new(0.0, 0.0, 0.0));
+    assert_eq!(texture.value(0.75, 0.5, &p), Point::new(1.0, 1.0, 1.0));
+    assert_eq!(texture.value(0.5, 0.5, &p), Point::new(0.5, 0.5, 0.5));
+    assert_eq!(texture.value(0.0, 0.5, &p), Point::new(0.0, 0.0, 0.0));
+
+    let nearest = ImageTexture::new(texture.image, false);
+    assert_eq!(nearest.value(0.5, 0.5, &p), Point::new(1.0, 1.0, 1.0));
+}
+
+#[test]
+fn wrap_modes_outside_the_unit_square() {
+    let mut image = RgbImage::new(4, 1);
+    for i in 0..4 {
+        image.put_pixel(i, 0, image::Rgb([i as u8, 0, 0]));
+    }
+    let texture = ImageTexture::new(image, false);
+    let p = Point::default();
+    let column = |texture: &ImageTexture, u| {
+        let red = texture.value(u, 0.5, &p).x();
+        (red.sqrt() * 255.0).round() as u8
+    };
+
+    let texture = texture.with_wrap(WrapMode::Repeat);
+    assert_eq!((column(&texture, 1.5), column(&texture, -0.25)), (2, 3));
+    let texture = texture.with_wrap(WrapMode::Clamp);
+    assert_eq!((column(&texture, 1.5), column(&texture, -0.25)), (3, 0));
+    let texture = texture.with_wrap(WrapMode::Mirror);
+    assert_eq!((column(&texture, 1.5), column(&texture, -0.25)), (1, 0));
+}
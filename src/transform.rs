@@ -0,0 +1,465 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable, Interval},
+    matrix::Mat4,
+    point::{Point, Vector},
+    ray::Ray,
+    scene::Json,
+};
+
+/// Places a child `Hittable` in the world through an affine `Mat4`.
+pub struct Transform {
+    object: Box<dyn Hittable>,
+    matrix: Mat4,
+    inverse: Mat4,
+    normal_matrix: Mat4,
+    /// How much `inverse` scales volumes, for converting light densities.
+    inverse_volume: f64,
+}
+
+impl Transform {
+    /// `None` if `matrix` is not invertible.
+    pub fn new(object: Box<dyn Hittable>, matrix: Mat4) -> Option<Self> {
+        let inverse = matrix.inverse()?;
+        let axis = |x, y, z| inverse.transform_vector(&Vector::new(x, y, z));
+        let (a, b, c) = (
+            axis(1.0, 0.0, 0.0),
+            axis(0.0, 1.0, 0.0),
+            axis(0.0, 0.0, 1.0),
+        );
+        Some(Self {
+            object,
+            matrix,
+            inverse,
+            normal_matrix: inverse.transpose(),
+            inverse_volume: a.dot(&b.cross(&c)).abs(),
+        })
+    }
+
+    fn object_ray(&self, r: &Ray) -> Ray {
+        Ray::new(
+            self.inverse.transform_point(&r.origin()),
+            self.inverse.transform_vector(&r.direction()),
+        )
+        .with_time(r.time())
+        .with_primary(r.is_primary())
+    }
+}
+
+impl Hittable for Transform {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let mut record = self.object.hit(&self.object_ray(r), ray_t)?;
+        record.p = self.matrix.transform_point(&record.p);
+        record.normal = self.normal_matrix.transform_vector(&record.normal).unit()?;
+        let along = |v: Option<Vector>| self.matrix.transform_vector(&v?).unit();
+        (record.tangent, record.bitangent) = (along(record.tangent), along(record.bitangent));
+        Some(record)
+    }
+
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.object.hit_distance(&self.object_ray(r), ray_t)
+    }
+
+    fn hit_any(&self, r: &Ray, ray_t: &Interval) -> bool {
+        self.object.hit_any(&self.object_ray(r), ray_t)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let corners = self.object.bounding_box()?.corners();
+        Aabb::around_points(corners.iter().map(|x| self.matrix.transform_point(x)))
+    }
+
+    fn boxes_pierced(&self, r: &Ray, ray_t: &Interval) -> u32 {
+        let own = u32::from(self.bounding_box().is_some_and(|b| b.hit(r, ray_t)));
+        own + self.object.boxes_pierced(&self.object_ray(r), ray_t)
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        let (center, radius) = self.object.bounding_sphere()?;
+        let stretch = [
+            Point::new(1.0, 0.0, 0.0),
+            Point::new(0.0, 1.0, 0.0),
+            Point::new(0.0, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|axis| self.matrix.transform_vector(axis).len())
+        .fold(0.0, f64::max);
+        Some((self.matrix.transform_point(&center), radius * stretch))
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        let Some(direction) = direction.unit() else {
+            return 0.0;
+        };
+        let local = self.inverse.transform_vector(&direction);
+        let density = self
+            .object
+            .pdf_value(&self.inverse.transform_point(origin), &local);
+        density * solid_angle_scale(self.inverse_volume, local)
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        let local = self.object.random(&self.inverse.transform_point(origin));
+        self.matrix.transform_vector(&local)
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        let rows = self
+            .matrix
+            .rows()
+            .map(|row| Json::Array(row.map(Json::from).to_vec()));
+        Some(Json::tagged(
+            "transform",
+            [
+                ("matrix", Json::Array(rows.to_vec())),
+                ("object", self.object.to_json()?),
+            ],
+        ))
+    }
+}
+
+/// One placement of a `Hittable` shared with others, so a mesh can appear
+/// many times in the world while stored once.
+pub struct Instance {
+    transform: Transform,
+}
+
+impl Instance {
+    /// `None` if `matrix` is not invertible.
+    pub fn new(object: Arc<dyn Hittable>, matrix: Mat4) -> Option<Self> {
+        Some(Self {
+            transform: Transform::new(Box::new(Shared(object)), matrix)?,
+        })
+    }
+}
+
+impl Hittable for Instance {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        self.transform.hit(r, ray_t)
+    }
+
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.transform.hit_distance(r, ray_t)
+    }
+
+    fn hit_any(&self, r: &Ray, ray_t: &Interval) -> bool {
+        self.transform.hit_any(r, ray_t)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.transform.bounding_box()
+    }
+
+    fn boxes_pierced(&self, r: &Ray, ray_t: &Interval) -> u32 {
+        self.transform.boxes_pierced(r, ray_t)
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        self.transform.bounding_sphere()
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        self.transform.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        self.transform.random(origin)
+    }
+
+    /// Saved as a `Transform` of its own copy of the object.
+    fn to_json(&self) -> Option<Json> {
+        self.transform.to_json()
+    }
+}
+
+/// The shared object of an `Instance`, as the child of its `Transform`.
+struct Shared(Arc<dyn Hittable>);
+
+impl Hittable for Shared {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        self.0.hit(r, ray_t)
+    }
+
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.0.hit_distance(r, ray_t)
+    }
+
+    fn hit_any(&self, r: &Ray, ray_t: &Interval) -> bool {
+        self.0.hit_any(r, ray_t)
+    }
+
+    fn hit_into(&self, r: &Ray, ray_t: &Interval, out: &mut HitRecord) -> bool {
+        self.0.hit_into(r, ray_t, out)
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        self.0.bounding_sphere()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.0.bounding_box()
+    }
+
+    fn boxes_pierced(&self, r: &Ray, ray_t: &Interval) -> u32 {
+        self.0.boxes_pierced(r, ray_t)
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        self.0.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        self.0.random(origin)
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        self.0.to_json()
+    }
+}
+
+/// Scales a child `Hittable` by (possibly different) factors along each axis.
+pub struct Scale {
+    object: Box<dyn Hittable>,
+    factors: Vector,
+    inverse: Vector,
+}
+
+impl Scale {
+    /// `None` if any factor is zero.
+    pub fn new(object: Box<dyn Hittable>, factors: Vector) -> Option<Self> {
+        if factors.x() == 0.0 || factors.y() == 0.0 || factors.z() == 0.0 {
+            return None;
+        }
+        Some(Self {
+            object,
+            factors,
+            inverse: Vector::new(1.0 / factors.x(), 1.0 / factors.y(), 1.0 / factors.z()),
+        })
+    }
+
+    /// The object-space direction is left unnormalized, so `t` measures the
+    /// same point along both rays and needs no adjustment on the way back.
+    fn object_ray(&self, r: &Ray) -> Ray {
+        Ray::new(r.origin() * self.inverse, r.direction() * self.inverse)
+            .with_time(r.time())
+            .with_primary(r.is_primary())
+    }
+}
+
+impl Hittable for Scale {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let mut record = self.object.hit(&self.object_ray(r), ray_t)?;
+        record.p = record.p * self.factors;
+        record.normal = (record.normal * self.inverse).unit()?;
+        let along = |v: Option<Vector>| (v? * self.factors).unit();
+        (record.tangent, record.bitangent) = (along(record.tangent), along(record.bitangent));
+        Some(record)
+    }
+
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.object.hit_distance(&self.object_ray(r), ray_t)
+    }
+
+    fn hit_any(&self, r: &Ray, ray_t: &Interval) -> bool {
+        self.object.hit_any(&self.object_ray(r), ray_t)
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        let aabb = self.object.bounding_box()?;
+        Some(Aabb::new(
+            aabb.min() * self.factors,
+            aabb.max() * self.factors,
+        ))
+    }
+
+    fn boxes_pierced(&self, r: &Ray, ray_t: &Interval) -> u32 {
+        let own = u32::from(self.bounding_box().is_some_and(|b| b.hit(r, ray_t)));
+        own + self.object.boxes_pierced(&self.object_ray(r), ray_t)
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        let (center, radius) = self.object.bounding_sphere()?;
+        let stretch = self
+            .factors
+            .x()
+            .abs()
+            .max(self.factors.y().abs())
+            .max(self.factors.z().abs());
+        Some((center * self.factors, radius * stretch))
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        let Some(direction) = direction.unit() else {
+            return 0.0;
+        };
+        let local = direction * self.inverse;
+        let volume = (self.inverse.x() * self.inverse.y() * self.inverse.z()).abs();
+        self.object.pdf_value(&(*origin * self.inverse), &local) * solid_angle_scale(volume, local)
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        self.object.random(&(*origin * self.inverse)) * self.factors
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "scale",
+            [
+                ("factors", self.factors.into()),
+                ("object", self.object.to_json()?),
+            ],
+        ))
+    }
+}
+
+/// How much a linear map scaling volumes by `volume` stretches solid angle
+/// around the unit direction it takes to `image`, to turn a density over
+/// the image's directions into one over the originals.
+fn solid_angle_scale(volume: f64, image: Vector) -> f64 {
+    volume / image.len_squared().powf(1.5)
+}
+
+#[test]
+fn transformed_sphere_is_hit_where_placed() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let sphere = Box::new(Sphere::new(Point::new(1.0, 0.0, 0.0), 0.5, mat));
+    let matrix = Mat4::translation(Vector::new(0.0, 0.0, -5.0)) * Mat4::rotation_y(90.0);
+    let transform = Transform::new(sphere, matrix).unwrap();
+
+    let ray = Ray::new(Point::default(), Vector::new(0.0, 0.0, -1.0));
+    let record = transform
+        .hit(&ray, &Interval::new_set_interval(0.001, f64::MAX))
+        .unwrap();
+    assert!((record.t - 5.5).abs() < 1e-9);
+    assert!((record.p - Point::new(0.0, 0.0, -5.5)).len() < 1e-9);
+    assert!((record.normal - Vector::new(0.0, 0.0, 1.0)).len() < 1e-9);
+
+    let (center, radius) = transform.bounding_sphere().unwrap();
+    assert!((center - Point::new(0.0, 0.0, -6.0)).len() < 1e-9);
+    assert!((radius - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn uniform_scale_matches_larger_sphere() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let center = Point::new(0.0, 0.5, -2.0);
+    let unit = Box::new(Sphere::new(center, 1.0, mat.clone()));
+    let scaled = Scale::new(unit, Vector::new(2.0, 2.0, 2.0)).unwrap();
+    let larger = Sphere::new(center * 2.0, 2.0, mat);
+
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let ray = Ray::new(Point::new(0.3, 0.2, 5.0), Vector::new(0.0, 0.1, -1.0));
+    let expected = larger.hit(&ray, &interval).unwrap();
+    let record = scaled.hit(&ray, &interval).unwrap();
+    assert!((record.t - expected.t).abs() < 1e-9);
+    assert!((record.p - expected.p).len() < 1e-9);
+    assert!((record.normal - expected.normal).len() < 1e-9);
+}
+
+#[test]
+fn transformed_lights_sample_what_they_cover() {
+    use std::f64::consts::PI;
+
+    use crate::{lambertian::Lambertian, quad::Quad, sphere::Sphere};
+
+    crate::utils::seed_random(1);
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let quad = || {
+        Box::new(Quad::new(
+            Point::new(-0.5, -0.5, 0.0),
+            Vector::new(1.0, 0.0, 0.0),
+            Vector::new(0.0, 1.0, 0.0),
+            mat.clone(),
+        ))
+    };
+    let matrix = Mat4::translation(Vector::new(0.0, 0.0, -2.0))
+        * Mat4::rotation_y(30.0)
+        * Mat4::scaling(Vector::new(2.0, 1.0, 1.0));
+    let lights: [Box<dyn Hittable>; 2] = [
+        Box::new(Transform::new(quad(), matrix).unwrap()),
+        Box::new(
+            Scale::new(
+                Box::new(Sphere::new(Point::new(0.0, 0.0, -3.0), 1.0, mat.clone())),
+                Vector::new(1.0, 2.0, 0.5),
+            )
+            .unwrap(),
+        ),
+    ];
+    let origin = Point::new(0.1, 0.2, 0.0);
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let n = 40_000;
+    for light in lights {
+        // The solid angle covered, from evenly spread directions and from the
+        // light's own, weighted by how likely it was to pick them.
+        let (mut even, mut sampled) = (0.0, 0.0);
+        for _ in 0..n {
+            let d = Point::random_in_unit_vector().unwrap();
+            let density = light.pdf_value(&origin, &d);
+            let hit = light.hit(&Ray::new(origin, d), &interval).is_some();
+            assert_eq!(density > 0.0, hit);
+            even += 4.0 * PI * f64::from(u8::from(hit));
+            let d = light.random(&origin);
+            sampled += 1.0 / light.pdf_value(&origin, &d);
+        }
+        let (even, sampled) = (even / f64::from(n), sampled / f64::from(n));
+        assert!(
+            (even - sampled).abs() < 0.05 * even,
+            "{} and {}",
+            even,
+            sampled
+        );
+    }
+}
+
+#[test]
+fn instances_share_one_sphere_in_two_places() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let sphere: Arc<dyn Hittable> = Arc::new(Sphere::new(Point::default(), 0.5, mat));
+    let place = |x| Instance::new(sphere.clone(), Mat4::translation(Vector::new(x, 0.0, -3.0)));
+    let (left, right) = (place(-2.0).unwrap(), place(2.0).unwrap());
+    assert_eq!(Arc::strong_count(&sphere), 3);
+
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let ray = |x| Ray::new(Point::new(x, 0.0, 0.0), Vector::new(0.0, 0.0, -1.0));
+    for (instance, x) in [(&left, -2.0), (&right, 2.0)] {
+        let record = instance.hit(&ray(x), &interval).unwrap();
+        assert!((record.p - Point::new(x, 0.0, -2.5)).len() < 1e-9);
+        assert!(!instance.hit_any(&ray(-x), &interval));
+    }
+    assert!(sphere.hit_any(&ray(0.0), &interval));
+}
+
+#[test]
+fn hit_distances_match_the_records() {
+    use crate::{lambertian::Lambertian, sphere::Sphere};
+
+    let mat = Arc::new(Lambertian::new(Point::default()));
+    let sphere = || Box::new(Sphere::new(Point::default(), 1.0, mat.clone()));
+    let matrix = Mat4::translation(Vector::new(0.5, 0.0, -4.0)) * Mat4::rotation_y(30.0);
+    let shared: Arc<dyn Hittable> = Arc::new(Sphere::new(Point::default(), 1.0, mat.clone()));
+    let objects: [Box<dyn Hittable>; 3] = [
+        Box::new(Transform::new(sphere(), matrix).unwrap()),
+        Box::new(
+            Scale::new(
+                Box::new(Sphere::new(Point::new(0.0, 0.0, -2.0), 1.0, mat.clone())),
+                Vector::new(1.0, 0.5, 2.0),
+            )
+            .unwrap(),
+        ),
+        Box::new(Instance::new(shared, matrix).unwrap()),
+    ];
+    let ray = Ray::new(Point::new(0.2, 0.1, 0.0), Vector::new(0.0, 0.0, -1.0));
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    for object in objects {
+        let record = object.hit(&ray, &interval).unwrap();
+        assert_eq!(object.hit_distance(&ray, &interval), Some(record.t));
+    }
+}
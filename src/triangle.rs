@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable, Interval},
+    material::Material,
+    point::{Point, Vector},
+    ray::Ray,
+    scene::Json,
+    utils::random_between,
+};
+
+pub struct Triangle {
+    vertices: [Point; 3],
+    normals: Option<[Vector; 3]>,
+    mat: Arc<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(a: Point, b: Point, c: Point, mat: Arc<dyn Material>) -> Self {
+        Self {
+            vertices: [a, b, c],
+            normals: None,
+            mat,
+        }
+    }
+
+    /// A triangle shaded with `normals` (one per vertex, in the same order)
+    /// interpolated across its face instead of its flat geometric normal.
+    pub fn with_normals(
+        a: Point,
+        b: Point,
+        c: Point,
+        normals: [Vector; 3],
+        mat: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            vertices: [a, b, c],
+            normals: Some(normals),
+            mat,
+        }
+    }
+
+    fn shading_normal(&self, geometric: Vector, u: f64, v: f64) -> Vector {
+        match self.normals {
+            Some([na, nb, nc]) => ((1.0 - u - v) * na + u * nb + v * nc)
+                .unit()
+                .unwrap_or(geometric),
+            None => geometric,
+        }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        let [a, b, c] = self.vertices;
+        let edge1 = b - a;
+        let edge2 = c - a;
+
+        let pvec = r.direction().cross(&edge2);
+        let det = edge1.dot(&pvec);
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let tvec = r.origin() - a;
+        let u = tvec.dot(&pvec) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let qvec = tvec.cross(&edge1);
+        let v = r.direction().dot(&qvec) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = edge2.dot(&qvec) * inv_det;
+        if !ray_t.surrounds(t) {
+            return None;
+        }
+
+        let geometric = edge1.cross(&edge2).unit()?;
+        let front_face = r.direction().dot(&geometric) < 0.0;
+        let normal = self.shading_normal(geometric, u, v);
+        // `u` and `v` run along the edges, in the plane of the shading normal.
+        let along = |edge: Vector| (edge - normal * edge.dot(&normal)).unit();
+        let (tangent, bitangent) = (along(edge1), along(edge2));
+        let normal = if front_face { normal } else { -normal };
+        Some(HitRecord {
+            t,
+            u,
+            v,
+            p: r.at(t),
+            normal,
+            front_face,
+            tangent,
+            bitangent,
+            id: None,
+            mat: self.mat.clone(),
+        })
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        Some(Aabb::around_points(self.vertices)?.padded(1e-4))
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        let [a, b, c] = self.vertices;
+        let center = ((a + b + c) / 3.0)?;
+        let radius = self
+            .vertices
+            .iter()
+            .map(|x| (*x - center).len())
+            .fold(0.0, f64::max);
+        Some((center, radius))
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        let ray = Ray::new(*origin, *direction);
+        let Some(t) = self.hit_distance(&ray, &Interval::new_set_interval(0.001, f64::MAX)) else {
+            return 0.0;
+        };
+        let [a, b, c] = self.vertices;
+        let cross = (b - a).cross(&(c - a));
+        let distance_squared = t * t * direction.len_squared();
+        let cosine = (direction.dot(&cross) / (direction.len() * cross.len())).abs();
+        distance_squared / (cosine * 0.5 * cross.len())
+    }
+
+    /// A point drawn evenly over the face.
+    fn random(&self, origin: &Point) -> Vector {
+        let [a, b, c] = self.vertices;
+        let (r1, r2) = (random_between(0.0, 1.0).sqrt(), random_between(0.0, 1.0));
+        let p = (1.0 - r1) * a + r1 * (1.0 - r2) * b + r1 * r2 * c;
+        p - *origin
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        let points = |points: [Point; 3]| Json::Array(points.map(Json::from).to_vec());
+        let mut json = Json::tagged(
+            "triangle",
+            [
+                ("vertices", points(self.vertices)),
+                ("material", self.mat.to_json()?),
+            ],
+        );
+        if let (Json::Object(fields), Some(normals)) = (&mut json, self.normals) {
+            fields.push(("normals".to_string(), points(normals)));
+        }
+        Some(json)
+    }
+}
+
+#[test]
+fn interpolates_vertex_normals_at_centroid() {
+    use crate::lambertian::Lambertian;
+
+    let triangle = Triangle::with_normals(
+        Point::new(0.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Point::new(0.0, 1.0, 0.0),
+        [
+            Vector::new(1.0, 0.0, 1.0).unit().unwrap(),
+            Vector::new(0.0, 1.0, 1.0).unit().unwrap(),
+            Vector::new(-1.0, -1.0, 1.0).unit().unwrap(),
+        ],
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let centroid = Point::new(1.0 / 3.0, 1.0 / 3.0, 0.0);
+    let ray = Ray::new(
+        centroid + Vector::new(0.0, 0.0, 1.0),
+        Vector::new(0.0, 0.0, -1.0),
+    );
+
+    let record = triangle
+        .hit(&ray, &Interval::new_set_interval(0.001, f64::MAX))
+        .unwrap();
+    assert!(record.front_face);
+    assert!((record.t - 1.0).abs() < 1e-9);
+
+    let a = 1.0 / 2.0_f64.sqrt();
+    let b = 1.0 / 3.0_f64.sqrt();
+    let expected = Vector::new(a - b, a - b, 2.0 * a + b).unit().unwrap();
+    assert!((record.normal - expected).len() < 1e-9);
+}
+
+#[test]
+fn samples_directions_toward_its_face() {
+    use crate::lambertian::Lambertian;
+    use std::f64::consts::PI;
+
+    let triangle = Triangle::new(
+        Point::new(-1.0, 0.0, -1.0),
+        Point::new(1.0, 0.0, -1.5),
+        Point::new(0.0, 1.5, -1.0),
+        Arc::new(Lambertian::new(Point::default())),
+    );
+    let origin = Point::new(0.0, 0.3, 0.0);
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let n = 40_000;
+    let (mut even, mut sampled) = (0.0, 0.0);
+    for _ in 0..n {
+        let d = Point::random_in_unit_vector().unwrap();
+        let hit = triangle.hit(&Ray::new(origin, d), &interval).is_some();
+        assert_eq!(triangle.pdf_value(&origin, &d) > 0.0, hit);
+        even += 4.0 * PI * f64::from(u8::from(hit));
+        let d = triangle.random(&origin);
+        sampled += 1.0 / triangle.pdf_value(&origin, &d);
+    }
+    let (even, sampled) = (even / f64::from(n), sampled / f64::from(n));
+    assert!(
+        (even - sampled).abs() < 0.05 * even,
+        "{} and {}",
+        even,
+        sampled
+    );
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind, ScatterRecord},
+    point::Point,
+    ray::Ray,
+    scene::Json,
+};
+
+/// One material on the front face of a surface, the outside its normal
+/// points to, and another on the back: a leaf green in front and pale
+/// behind, or a mirror that is glass from the other side.
+pub struct TwoSided {
+    front: Arc<dyn Material>,
+    back: Arc<dyn Material>,
+}
+
+impl TwoSided {
+    pub fn new(front: Arc<dyn Material>, back: Arc<dyn Material>) -> Self {
+        Self { front, back }
+    }
+
+    fn side(&self, rec: &HitRecord) -> &dyn Material {
+        if rec.front_face {
+            self.front.as_ref()
+        } else {
+            self.back.as_ref()
+        }
+    }
+}
+
+impl Material for TwoSided {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::TwoSided
+    }
+
+    /// Only if both sides are, as this can't tell which side it is.
+    fn is_specular(&self) -> bool {
+        self.front.is_specular() && self.back.is_specular()
+    }
+
+    fn lights_scene(&self) -> bool {
+        self.front.lights_scene() || self.back.lights_scene()
+    }
+
+    fn scatter(&self, r_in: &Ray, rec: &HitRecord) -> Option<(Point, Ray)> {
+        self.side(rec).scatter(r_in, rec)
+    }
+
+    fn scatter_record(&self, r_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        self.side(rec).scatter_record(r_in, rec)
+    }
+
+    fn scattering_pdf(&self, r_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        self.side(rec).scattering_pdf(r_in, rec, scattered)
+    }
+
+    fn emitted(&self, r_in: &Ray, rec: &HitRecord) -> Point {
+        self.side(rec).emitted(r_in, rec)
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "two_sided",
+            [
+                ("front", self.front.to_json()?),
+                ("back", self.back.to_json()?),
+            ],
+        ))
+    }
+}
+
+#[test]
+fn each_face_scatters_like_its_own_material() {
+    use crate::lambertian::Lambertian;
+
+    let green = Point::new(0.1, 0.8, 0.1);
+    let pale = Point::new(0.8, 0.9, 0.7);
+    let leaf: Arc<dyn Material> = Arc::new(TwoSided::new(
+        Arc::new(Lambertian::new(green)),
+        Arc::new(Lambertian::new(pale)),
+    ));
+    let hit = |front_face| HitRecord {
+        p: Point::default(),
+        normal: Point::new(0.0, 1.0, 0.0),
+        t: 1.0,
+        u: 0.0,
+        v: 0.0,
+        front_face,
+        tangent: None,
+        bitangent: None,
+        id: None,
+        mat: leaf.clone(),
+    };
+    let ray = Ray::new(Point::new(0.0, 1.0, 0.0), Point::new(0.0, -1.0, 0.0));
+    assert_eq!(leaf.scatter(&ray, &hit(true)).unwrap().0, green);
+    assert_eq!(leaf.scatter(&ray, &hit(false)).unwrap().0, pale);
+    assert_eq!(
+        leaf.scatter_record(&ray, &hit(false)).unwrap().attenuation,
+        pale
+    );
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    material::{Material, MaterialKind},
+    point::Point,
+    ray::Ray,
+    scene::Json,
+    texture::{SolidColor, Texture},
+};
+
+/// A flat color with no shading, for markers, backdrops or a sky dome. It
+/// shows up at exactly its color, from either side, to the camera and in
+/// mirrors and glass, but unlike a light it gives surfaces around it no
+/// light.
+pub struct Unlit {
+    color: Arc<dyn Texture>,
+}
+
+impl Unlit {
+    pub fn new(color: Point) -> Self {
+        Self::with_texture(Arc::new(SolidColor::new(color)))
+    }
+
+    pub fn with_texture(color: Arc<dyn Texture>) -> Self {
+        Self { color }
+    }
+}
+
+impl Material for Unlit {
+    fn kind(&self) -> MaterialKind {
+        MaterialKind::Unlit
+    }
+
+    fn lights_scene(&self) -> bool {
+        false
+    }
+
+    fn scatter(&self, _r_in: &Ray, _rec: &HitRecord) -> Option<(Point, Ray)> {
+        None
+    }
+
+    fn emitted(&self, _r_in: &Ray, rec: &HitRecord) -> Point {
+        self.color.value(rec.u, rec.v, &rec.p)
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged("unlit", [("color", self.color.to_json()?)]))
+    }
+}
+
+#[test]
+fn unlit_spheres_render_their_exact_color_and_light_nothing() {
+    use crate::{
+        camera::{test_init, Background, Camera, CameraInit},
+        hittable::HittableList,
+        lambertian::Lambertian,
+        sphere::Sphere,
+    };
+
+    let camera = Camera::new(
+        1.0,
+        4,
+        CameraInit {
+            samples_per_pixel: 4,
+            background: Background::Solid(Point::default()),
+            ..test_init()
+        },
+    );
+    // Gamma turns linear 0.25 into 0.5, or 127 of 255.
+    let color = Point::new(1.0, 0.25, 0.0);
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -10.0),
+        9.0,
+        Arc::new(Unlit::new(color)),
+    )));
+    let image = camera.render_to_buffer(&world);
+    assert!(image.pixels().all(|p| p.0 == [255, 127, 0]));
+
+    // Seen from inside, as a dome around the camera, it is just as flat, and
+    // a white sphere within it stays black.
+    let mut world = HittableList::new(None);
+    world.add(Box::new(Sphere::new(
+        Point::default(),
+        100.0,
+        Arc::new(Unlit::new(color)),
+    )));
+    world.add(Box::new(Sphere::new(
+        Point::new(0.0, 0.0, -2.0),
+        1.0,
+        Arc::new(Lambertian::new(Point::new(1.0, 1.0, 1.0))),
+    )));
+    let image = camera.render_to_buffer(&world);
+    assert_eq!(image.get_pixel(0, 0).0, [255, 127, 0]);
+    assert_eq!(image.get_pixel(2, 2).0, [0, 0, 0]);
+}
@@ -1,6 +1,37 @@
-use rand::prelude::*;
+use std::cell::RefCell;
+
+use rand::{prelude::*, rngs::StdRng};
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// Reseeds the current thread's generator so the values drawn from it from
+/// now on are reproducible.
+pub fn seed_random(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
 
 pub fn random_between(min: f64, max: f64) -> f64 {
-    let mut rng = thread_rng();
-    rng.gen_range(min..max)
+    RNG.with(|rng| rng.borrow_mut().gen_range(min..max))
+}
+
+/// Schlick's approximation of the Fresnel reflectance at an interface with
+/// relative refractive index `ref_idx`.
+pub fn schlick(cosine: f64, ref_idx: f64) -> f64 {
+    let r0 = (1.0 - ref_idx) / (1.0 + ref_idx);
+    schlick_f0(cosine, r0 * r0)
+}
+
+/// Schlick's approximation given the reflectance `f0` at normal incidence.
+pub fn schlick_f0(cosine: f64, f0: f64) -> f64 {
+    f0 + (1.0 - f0) * (1.0 - cosine).powi(5)
+}
+
+#[test]
+fn schlick_matches_known_reflectance() {
+    assert!((schlick(1.0, 1.5) - 0.04).abs() < 1e-12);
+    assert!((schlick(1.0, 1.0 / 1.5) - 0.04).abs() < 1e-12);
+    assert_eq!(schlick(0.0, 1.5), 1.0);
+    assert!((schlick_f0(0.5, 0.04) - (0.04 + 0.96 / 32.0)).abs() < 1e-12);
 }
@@ -0,0 +1,130 @@
+use crate::{
+    aabb::Aabb,
+    hittable::{HitRecord, Hittable, Interval},
+    point::{Point, Vector},
+    ray::Ray,
+    scene::Json,
+};
+
+/// Hides a child `Hittable` from camera rays or from every other ray, for
+/// compositing. Hidden from the camera, it still casts shadows, shows in
+/// reflections and lights the scene; hidden from `shadows`, the bounces and
+/// probes that carry them, it shows up in the image but leaves the rest of
+/// the scene as if it weren't there.
+pub struct Visibility {
+    child: Box<dyn Hittable>,
+    camera: bool,
+    shadows: bool,
+}
+
+impl Visibility {
+    pub fn new(child: Box<dyn Hittable>, camera: bool, shadows: bool) -> Self {
+        Self {
+            child,
+            camera,
+            shadows,
+        }
+    }
+
+    fn sees(&self, r: &Ray) -> bool {
+        if r.is_primary() {
+            self.camera
+        } else {
+            self.shadows
+        }
+    }
+}
+
+impl Hittable for Visibility {
+    fn hit(&self, r: &Ray, ray_t: &Interval) -> Option<HitRecord> {
+        self.sees(r).then(|| self.child.hit(r, ray_t))?
+    }
+
+    fn hit_distance(&self, r: &Ray, ray_t: &Interval) -> Option<f64> {
+        self.sees(r).then(|| self.child.hit_distance(r, ray_t))?
+    }
+
+    fn hit_any(&self, r: &Ray, ray_t: &Interval) -> bool {
+        self.sees(r) && self.child.hit_any(r, ray_t)
+    }
+
+    fn hit_into(&self, r: &Ray, ray_t: &Interval, out: &mut HitRecord) -> bool {
+        self.sees(r) && self.child.hit_into(r, ray_t, out)
+    }
+
+    fn bounding_sphere(&self) -> Option<(Point, f64)> {
+        self.child.bounding_sphere()
+    }
+
+    fn bounding_box(&self) -> Option<Aabb> {
+        self.child.bounding_box()
+    }
+
+    fn boxes_pierced(&self, r: &Ray, ray_t: &Interval) -> u32 {
+        self.child.boxes_pierced(r, ray_t)
+    }
+
+    fn pdf_value(&self, origin: &Point, direction: &Vector) -> f64 {
+        self.child.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: &Point) -> Vector {
+        self.child.random(origin)
+    }
+
+    fn to_json(&self) -> Option<Json> {
+        Some(Json::tagged(
+            "visibility",
+            [
+                ("camera", self.camera.into()),
+                ("shadows", self.shadows.into()),
+                ("object", self.child.to_json()?),
+            ],
+        ))
+    }
+}
+
+#[test]
+fn objects_hidden_from_the_camera_still_cast_shadows() {
+    use std::sync::Arc;
+
+    use crate::{
+        camera::{test_init, Background, Camera, CameraInit},
+        hittable::HittableList,
+        lambertian::Lambertian,
+        sphere::Sphere,
+    };
+
+    let sphere = || {
+        Box::new(Sphere::new(
+            Point::new(0.0, 0.0, -2.0),
+            1.0,
+            Arc::new(Lambertian::new(Point::new(0.5, 0.5, 0.5))),
+        ))
+    };
+    let hidden = Visibility::new(sphere(), false, true);
+    let interval = Interval::new_set_interval(0.001, f64::MAX);
+    let toward = Ray::new(Point::default(), Vector::new(0.0, 0.0, -1.0));
+
+    assert!(hidden.hit(&toward.with_primary(true), &interval).is_none());
+    assert!(!hidden.hit_any(&toward.with_primary(true), &interval));
+    assert!(hidden.hit_any(&toward, &interval));
+    assert!(hidden.hit(&toward, &interval).is_some());
+    let unlit = Visibility::new(sphere(), true, false);
+    assert!(!unlit.hit_any(&toward, &interval));
+    assert!(unlit.hit_any(&toward.with_primary(true), &interval));
+
+    // The camera sees right through it to the background.
+    let camera = Camera::new(
+        1.0,
+        4,
+        CameraInit {
+            samples_per_pixel: 2,
+            background: Background::Solid(Point::new(1.0, 0.0, 0.0)),
+            ..test_init()
+        },
+    );
+    let world = HittableList::new(Some(vec![Box::new(hidden)]));
+    let image = camera.render_to_buffer(&world);
+    assert!(image.pixels().all(|p| p.0 == [255, 0, 0]));
+}